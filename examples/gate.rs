@@ -10,8 +10,8 @@
 //! 6. Mix with original sound
 
 use fon::{mono::Mono64, Audio, Sink};
-use std::{convert::TryInto};
-use twang::{Mix, Room, Signal, Synth, Fc};
+use std::convert::TryInto;
+use twang::{Fc, Mix, Room, Signal, Synth};
 
 mod wav;
 
@@ -34,7 +34,7 @@ struct GateCx {
 fn gate(cx: &mut GateCx, _fc: Fc) -> Signal {
     let ofs = cx.counter as usize * 8;
     let input = f64::from_le_bytes([
-        cx.input.get(ofs + 0).cloned().unwrap_or(0),
+        cx.input.get(ofs).cloned().unwrap_or(0),
         cx.input.get(ofs + 1).cloned().unwrap_or(0),
         cx.input.get(ofs + 2).cloned().unwrap_or(0),
         cx.input.get(ofs + 3).cloned().unwrap_or(0),
@@ -49,7 +49,8 @@ fn gate(cx: &mut GateCx, _fc: Fc) -> Signal {
     // 2. Add Reverb
     cx.room.add(orig, (ECHO_TIME).try_into().unwrap(), REVERB);
     let reverb = cx.room.gen();
-    cx.room.add(reverb, (ECHO_TIME - 1).try_into().unwrap(), REVERB);
+    cx.room
+        .add(reverb, (ECHO_TIME - 1).try_into().unwrap(), REVERB);
     // 3. Compress (FIXME)
     let compressed = reverb;
     // 4. Apply Noise Gate (Side Chain Original Sound)
@@ -76,12 +77,11 @@ fn main() {
         S_RATE,
         (HOLD_TIME + DECAY_TIME).try_into().unwrap(),
     );
-    let input = std::fs::read(
-        std::env::args().skip(1).next().expect("Need a PCM file"),
-    )
-    .expect("Failed to read file");
+    let input =
+        std::fs::read(std::env::args().nth(1).expect("Need a PCM file"))
+            .expect("Failed to read file");
     /*let mut input = input.chunks_exact(8).map(|sample| {
-        
+
     });*/
 
     // Set up room for max 20 milliseconds reverb.
@@ -0,0 +1,27 @@
+use fon::{mono::Mono64, Audio, Sink};
+use twang::{Fc, Pluck, Signal, Synth};
+
+mod wav;
+
+// Target sample rate set to 48 KHz
+const S_RATE: u32 = 48_000;
+
+fn main() {
+    fn pluck(string: &mut Pluck, _fc: Fc) -> Signal {
+        string.gen().gain(0.7)
+    }
+
+    // Initialize audio with five seconds of silence.
+    let mut audio = Audio::<Mono64>::with_silence(S_RATE, S_RATE as usize * 5);
+    // Create a plucked string.
+    let mut string = Pluck::new(S_RATE);
+    string.pluck(220.0, 4.0, 0.5);
+    // Create the synthesizer.
+    let mut synth = Synth::new(string, pluck);
+
+    // Generate audio samples.
+    audio.sink(..).stream(&mut synth);
+
+    // Write synthesized audio to WAV file.
+    wav::write(audio, "pluck.wav").expect("Failed to write WAV file");
+}
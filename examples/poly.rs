@@ -0,0 +1,76 @@
+use fon::{mono::Mono64, Audio, Sink};
+use twang::{Adsr, Fc, Note, Poly, Signal, Steal, Synth, Velocity, Voice};
+
+mod wav;
+
+// Target sample rate set to 48 KHz
+const S_RATE: u32 = 48_000;
+
+/// One voice of the polyphonic synthesizer.
+#[derive(Clone, Debug)]
+struct Saw {
+    freq: f64,
+    gain: f64,
+    adsr: Adsr,
+}
+
+impl Voice for Saw {
+    fn note_on(&mut self, note: Note, velocity: Velocity) {
+        self.freq = note.freq();
+        self.gain = velocity.value();
+        self.adsr.note_on();
+    }
+
+    fn note_off(&mut self, _note: Note) {
+        self.adsr.note_off();
+    }
+
+    fn is_idle(&self) -> bool {
+        self.adsr.is_idle()
+    }
+
+    fn gen(&mut self, fc: Fc) -> Signal {
+        fc.saw_bl(self.freq)
+            .gain(self.adsr.gen())
+            .gain(self.gain * 0.15)
+    }
+}
+
+fn main() {
+    // Chords to play, one per second (A minor, F major, C major, G major)
+    const CHORDS: [[u8; 3]; 4] =
+        [[57, 60, 64], [53, 57, 60], [55, 60, 64], [55, 59, 62]];
+
+    // Initialize audio with five seconds of silence.
+    let mut audio = Audio::<Mono64>::with_silence(S_RATE, S_RATE as usize * 5);
+    // Create the synthesizer with 8 voices.
+    let adsr = Adsr::new(S_RATE, 0.01, 0.2, 0.6, 0.5);
+    let saw = Saw {
+        freq: 0.0,
+        gain: 0.0,
+        adsr,
+    };
+    let poly = Poly::<_, 8>::new(saw, Steal::Oldest);
+    let mut synth = Synth::new(poly, Poly::gen);
+
+    // Generate audio samples, playing a chord each second.
+    let second = S_RATE as usize;
+    for (i, chord) in CHORDS.iter().enumerate() {
+        for note in chord.iter() {
+            synth.note_on(Note::from_midi(*note), Velocity::from_midi(100));
+        }
+        audio
+            .sink(i * second..i * second + second * 3 / 4)
+            .stream(&mut synth);
+        for note in chord.iter() {
+            synth.note_off(Note::from_midi(*note));
+        }
+        audio
+            .sink(i * second + second * 3 / 4..(i + 1) * second)
+            .stream(&mut synth);
+    }
+    audio.sink(CHORDS.len() * second..).stream(&mut synth);
+
+    // Write synthesized audio to WAV file.
+    wav::write(audio, "poly.wav").expect("Failed to write WAV file");
+}
@@ -0,0 +1,95 @@
+use fon::{mono::Mono64, Audio, Sink};
+use twang::{
+    Adsr, Division, Fc, Groove, Mono, Note, NotePriority, Sequencer, Signal,
+    Step, Svf, Synth, Velocity, Voice,
+};
+
+mod wav;
+
+// Target sample rate set to 48 KHz
+const S_RATE: u32 = 48_000;
+// Tempo in beats per minute
+const BPM: f64 = 124.0;
+
+/// Acid bass voice.
+#[derive(Clone, Debug)]
+struct Bass {
+    freq: f64,
+    gain: f64,
+    adsr: Adsr,
+    filter: Svf,
+}
+
+impl Voice for Bass {
+    fn note_on(&mut self, note: Note, velocity: Velocity) {
+        self.freq = note.freq();
+        self.gain = velocity.value();
+        self.adsr.note_on();
+    }
+
+    fn note_off(&mut self, _note: Note) {
+        self.adsr.note_off();
+    }
+
+    fn is_idle(&self) -> bool {
+        self.adsr.is_idle()
+    }
+
+    fn gen(&mut self, fc: Fc) -> Signal {
+        let env = f64::from(self.adsr.gen());
+        let saw = fc.saw_bl(self.freq);
+        self.filter
+            .filter(saw, 200.0 + 2_000.0 * env * self.gain, 4.0)
+            .lowpass
+            .gain(env)
+            .gain(self.gain * 0.3)
+    }
+
+    // Slide between tied notes without restarting the envelope
+    fn legato(&mut self, _from: Note, note: Note, velocity: Velocity) {
+        self.freq = note.freq();
+        self.gain = velocity.value();
+    }
+}
+
+fn main() {
+    // Sixteen step pattern of MIDI notes (0 for rests), and which steps are
+    // tied
+    const PATTERN: [u8; 16] =
+        [33, 45, 0, 33, 43, 45, 0, 36, 33, 0, 45, 48, 33, 40, 43, 0];
+    const TIES: [usize; 2] = [4, 11];
+
+    // Initialize audio with eight seconds of silence.
+    let mut audio = Audio::<Mono64>::with_silence(S_RATE, S_RATE as usize * 8);
+    // Create the synthesizer.
+    let bass = Bass {
+        freq: 0.0,
+        gain: 0.0,
+        adsr: Adsr::new(S_RATE, 0.002, 0.15, 0.3, 0.05),
+        filter: Svf::new(S_RATE),
+    };
+    let mono = Mono::new(bass, NotePriority::Last, true);
+    let rate = Division::new(1, 16).hz(BPM);
+    let mut sequencer = Sequencer::new(mono, S_RATE, rate);
+    sequencer.groove(Groove::swing(0.58));
+    for (i, note) in PATTERN.iter().enumerate() {
+        let step = match *note {
+            0 => Step::rest(),
+            note => Step {
+                velocity: Velocity::new(if i % 4 == 0 { 1.0 } else { 0.7 }),
+                tie: TIES.contains(&i),
+                // Ghost notes only play some of the time
+                probability: if i % 2 == 1 { 0.75 } else { 1.0 },
+                ..Step::new(Note::from_midi(note))
+            },
+        };
+        sequencer.step(step);
+    }
+    let mut synth = Synth::new(sequencer, Sequencer::gen);
+
+    // Generate audio samples.
+    audio.sink(..).stream(&mut synth);
+
+    // Write synthesized audio to WAV file.
+    wav::write(audio, "sequencer.wav").expect("Failed to write WAV file");
+}
@@ -0,0 +1,35 @@
+use fon::{mono::Mono64, Audio, Sink};
+use twang::{Biquad, BiquadMode, Fc, Signal, Synth};
+
+mod wav;
+
+// Target sample rate set to 48 KHz
+const S_RATE: u32 = 48_000;
+
+#[derive(Debug)]
+struct Params {
+    lowpass: Biquad,
+}
+
+fn main() {
+    // Sweep a resonant lowpass filter over a sawtooth wave.
+    fn subtractive(params: &mut Params, fc: Fc) -> Signal {
+        let cutoff = 2000.0 + 1800.0 * f64::from(fc.freq(0.5).sine());
+        params
+            .lowpass
+            .filter(fc.saw_bl(110.0), cutoff, 4.0)
+            .gain(0.2)
+    }
+
+    // Initialize audio with five seconds of silence.
+    let mut audio = Audio::<Mono64>::with_silence(S_RATE, S_RATE as usize * 5);
+    // Create the synthesizer.
+    let lowpass = Biquad::new(S_RATE, BiquadMode::Lowpass);
+    let mut synth = Synth::new(Params { lowpass }, subtractive);
+
+    // Generate audio samples.
+    audio.sink(..).stream(&mut synth);
+
+    // Write synthesized audio to WAV file.
+    wav::write(audio, "subtractive.wav").expect("Failed to write WAV file");
+}
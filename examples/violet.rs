@@ -0,0 +1,24 @@
+use fon::{mono::Mono64, Audio, Sink};
+use twang::{Fc, Signal, Synth, Violet};
+
+mod wav;
+
+// Target sample rate set to 48 KHz
+const S_RATE: u32 = 48_000;
+
+fn main() {
+    fn gen_violet(violet: &mut Violet, _fc: Fc) -> Signal {
+        violet.noise()
+    }
+
+    // Initialize audio with five seconds of silence.
+    let mut audio = Audio::<Mono64>::with_silence(S_RATE, S_RATE as usize * 5);
+    // Create the synthesizer.
+    let mut synth = Synth::new(Violet::new(), gen_violet);
+
+    // Generate audio samples.
+    audio.sink(..).stream(&mut synth);
+
+    // Write synthesized audio to WAV file.
+    wav::write(audio, "violet.wav").expect("Failed to write WAV file");
+}
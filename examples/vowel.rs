@@ -0,0 +1,27 @@
+use fon::{mono::Mono64, Audio, Sink};
+use twang::{Fc, Signal, Synth, Vowel};
+
+mod wav;
+
+// Target sample rate set to 48 KHz
+const S_RATE: u32 = 48_000;
+
+fn main() {
+    // Morph from A to U over five seconds.
+    fn vowel(counter: &mut u32, fc: Fc) -> Signal {
+        let position = f64::from(*counter) / f64::from(S_RATE * 5);
+        *counter += 1;
+        Vowel::new(position).gen(&fc, 110.0).gain(0.5)
+    }
+
+    // Initialize audio with five seconds of silence.
+    let mut audio = Audio::<Mono64>::with_silence(S_RATE, S_RATE as usize * 5);
+    // Create the synthesizer.
+    let mut synth = Synth::new(0, vowel);
+
+    // Generate audio samples.
+    audio.sink(..).stream(&mut synth);
+
+    // Write synthesized audio to WAV file.
+    wav::write(audio, "vowel.wav").expect("Failed to write WAV file");
+}
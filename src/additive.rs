@@ -0,0 +1,123 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+use std::f64::consts::TAU;
+
+#[derive(Copy, Clone, Debug)]
+struct Partial {
+    phase: f64,
+    inc: f64,
+    amp: f64,
+    detune: f64,
+}
+
+/// Additive synthesis engine.  Sums harmonic partials that each have their own
+/// amplitude and detune, which may be changed every sample for envelopes.
+#[derive(Clone, Debug)]
+pub struct Additive {
+    partials: Vec<Partial>,
+    freq: f64,
+    sample_rate: f64,
+    table: Option<Vec<f64>>,
+}
+
+impl Additive {
+    /// Create a new additive synthesizer.
+    ///
+    /// - `sample_rate`: The sample rate of the output
+    /// - `amplitudes`: The starting amplitude of each harmonic partial
+    pub fn new<R: Into<f64>>(sample_rate: R, amplitudes: &[f64]) -> Self {
+        let partials = amplitudes
+            .iter()
+            .map(|amp| Partial {
+                phase: 0.0,
+                inc: 0.0,
+                amp: *amp,
+                detune: 1.0,
+            })
+            .collect();
+        Self {
+            partials,
+            freq: 0.0,
+            sample_rate: sample_rate.into(),
+            table: None,
+        }
+    }
+
+    /// Use a sine lookup table of `size` samples (linearly interpolated)
+    /// rather than calculating sine for every partial.
+    pub fn use_table(&mut self, size: usize) {
+        assert_ne!(size, 0, "Additive: table size must not be zero");
+        self.table = Some(
+            (0..=size)
+                .map(|i| (i as f64 / size as f64 * TAU).sin())
+                .collect(),
+        );
+    }
+
+    /// Set the fundamental frequency.
+    pub fn freq(&mut self, freq: f64) {
+        self.freq = freq;
+        for i in 0..self.partials.len() {
+            self.update(i);
+        }
+    }
+
+    /// Set the amplitude of harmonic partial number `partial` (0 is the
+    /// fundamental).
+    #[inline(always)]
+    pub fn amplitude<S: Into<Signal>>(&mut self, partial: usize, amp: S) {
+        self.partials[partial].amp = amp.into().into();
+    }
+
+    /// Set the detune in cents of harmonic partial number `partial` (0 is the
+    /// fundamental).
+    #[inline(always)]
+    pub fn detune<S: Into<Signal>>(&mut self, partial: usize, cents: S) {
+        let cents: f64 = cents.into().into();
+        self.partials[partial].detune = 2.0f64.powf(cents / 1200.0);
+        self.update(partial);
+    }
+
+    /// Generate the next sample.
+    #[inline(always)]
+    pub fn gen(&mut self) -> Signal {
+        let mut out = 0.0;
+        for partial in self.partials.iter_mut() {
+            // Skip partials above the nyquist frequency to prevent aliasing.
+            if partial.inc < 0.5 {
+                out += partial.amp * sine(&self.table, partial.phase);
+            }
+            partial.phase = (partial.phase + partial.inc).fract();
+        }
+        out.into()
+    }
+
+    /// Recalculate the phase increment for a partial.
+    #[inline(always)]
+    fn update(&mut self, i: usize) {
+        let partial = &mut self.partials[i];
+        partial.inc =
+            self.freq * (i + 1) as f64 * partial.detune / self.sample_rate;
+    }
+}
+
+/// Sine function of phase (0 to 1), optionally by table lookup.
+#[inline(always)]
+fn sine(table: &Option<Vec<f64>>, phase: f64) -> f64 {
+    if let Some(table) = table {
+        let pos = phase * (table.len() - 1) as f64;
+        let i = pos as usize;
+        table[i] + (table[i + 1] - table[i]) * pos.fract()
+    } else {
+        (phase * TAU).sin()
+    }
+}
@@ -0,0 +1,78 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{line::Line, sig::Signal};
+use std::f64::consts::PI;
+
+/// Schroeder allpass filter.  Passes all frequencies at equal gain while
+/// smearing their phase, for building reverbs.
+#[derive(Clone, Debug)]
+pub struct Allpass {
+    line: Line,
+}
+
+impl Allpass {
+    /// Create a new Schroeder allpass filter.
+    ///
+    /// - `max_delay`: The maximum delay in samples
+    #[inline(always)]
+    pub fn new(max_delay: usize) -> Self {
+        Self {
+            line: Line::new(max_delay),
+        }
+    }
+
+    /// Filter the next sample of a signal.
+    ///
+    /// - `delay`: The delay in samples (may be fractional)
+    /// - `coeff`: The allpass coefficient - range: -1~1 (exclusive)
+    #[inline(always)]
+    pub fn filter(&mut self, input: Signal, delay: f64, coeff: f64) -> Signal {
+        let delayed = self.line.read(delay);
+        let v = f64::from(input) + coeff * delayed;
+        self.line.push(v);
+        (delayed - coeff * v).into()
+    }
+}
+
+/// First-order allpass filter.  Shifts the phase of frequencies around a
+/// break frequency, for building phasers.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Allpass1 {
+    input: f64,
+    output: f64,
+}
+
+impl Allpass1 {
+    /// Create a new first-order allpass filter.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the allpass coefficient for a break frequency `freq` in Hz.
+    #[inline(always)]
+    pub fn coeff(sample_rate: f64, freq: f64) -> f64 {
+        let t = (PI * freq / sample_rate).tan();
+        (t - 1.0) / (t + 1.0)
+    }
+
+    /// Filter the next sample of a signal.
+    ///
+    /// - `coeff`: The allpass coefficient - range: -1~1 (exclusive)
+    #[inline(always)]
+    pub fn filter(&mut self, input: Signal, coeff: f64) -> Signal {
+        let x = f64::from(input);
+        let y = coeff * x + self.input - coeff * self.output;
+        self.input = x;
+        self.output = y;
+        y.into()
+    }
+}
@@ -0,0 +1,323 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    note::Note,
+    poly::Voice,
+    sig::Signal,
+    synth::Fc,
+    tempo::{Division, Transport},
+    velocity::Velocity,
+    white::White,
+};
+
+/// Order an [`Arpeggiator`] plays the held notes in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ArpMode {
+    /// Lowest to highest.
+    Up,
+    /// Highest to lowest.
+    Down,
+    /// Lowest to highest and back down (without repeating the highest and
+    /// lowest notes).
+    UpDown,
+    /// Random order.
+    Random,
+}
+
+/// Arpeggiator.  Plays the held notes one at a time on a [`Voice`] (such as a
+/// [`Poly`](crate::Poly) or [`Mono`](crate::Mono)) in [`ArpMode`] order,
+/// repeated over a range of octaves, at a clocked rate (or following a
+/// [`Transport`]).
+///
+/// ```rust
+/// use twang::{Adsr, ArpMode, Arpeggiator, Division, Fc, Note, Signal};
+/// use twang::{Velocity, Voice};
+///
+/// #[derive(Clone, Debug)]
+/// struct Square {
+///     freq: f64,
+///     adsr: Adsr,
+/// }
+///
+/// impl Voice for Square {
+///     fn note_on(&mut self, note: Note, _velocity: Velocity) {
+///         self.freq = note.freq();
+///         self.adsr.note_on();
+///     }
+///
+///     fn note_off(&mut self, _note: Note) {
+///         self.adsr.note_off();
+///     }
+///
+///     fn is_idle(&self) -> bool {
+///         self.adsr.is_idle()
+///     }
+///
+///     fn gen(&mut self, fc: Fc) -> Signal {
+///         fc.pulse_bl(self.freq, 0.0).gain(self.adsr.gen()).gain(0.3)
+///     }
+/// }
+///
+/// let adsr = Adsr::new(48_000, 0.005, 0.1, 0.5, 0.05);
+/// let square = Square { freq: 0.0, adsr };
+/// // Sixteenth notes at 120 BPM
+/// let rate = Division::new(1, 16).hz(120.0);
+/// let mut arp = Arpeggiator::new(square, 48_000, rate, ArpMode::UpDown);
+/// arp.octaves(2);
+/// for note in [57, 60, 64].iter() {
+///     arp.note_on(Note::from_midi(*note), Velocity::default());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Arpeggiator<T: Voice> {
+    voice: T,
+    sample_rate: f64,
+    // Steps per second
+    rate: f64,
+    mode: ArpMode,
+    octaves: u8,
+    gate: f64,
+    // Notes held down, sorted from lowest to highest
+    held: Vec<(Note, Velocity)>,
+    // Number of steps played since notes were first held (`None` before the
+    // first step)
+    step: Option<usize>,
+    // Progress through the current step (0 to 1)
+    phase: f64,
+    // Transport step of the current step (when synced)
+    tick: Option<u64>,
+    // Note the voice is playing
+    playing: Option<Note>,
+    white: White,
+}
+
+impl<T: Voice> Arpeggiator<T> {
+    /// Create a new arpeggiator over one octave, with a gate length of half
+    /// a step.
+    ///
+    /// - `rate`: Steps per second (see
+    ///   [`Division::hz()`](crate::Division::hz) for tempo sync)
+    pub fn new<R: Into<f64>>(
+        voice: T,
+        sample_rate: R,
+        rate: f64,
+        mode: ArpMode,
+    ) -> Self {
+        Self {
+            voice,
+            sample_rate: sample_rate.into(),
+            rate,
+            mode,
+            octaves: 1,
+            gate: 0.5,
+            held: Vec::new(),
+            step: None,
+            phase: 0.0,
+            tick: None,
+            playing: None,
+            white: White::new(),
+        }
+    }
+
+    /// Get the voice (for changing its parameters).
+    #[inline(always)]
+    pub fn voice(&mut self) -> &mut T {
+        &mut self.voice
+    }
+
+    /// Change the rate in steps per second.
+    #[inline(always)]
+    pub fn rate(&mut self, rate: f64) {
+        self.rate = rate;
+    }
+
+    /// Change the order notes are played in.
+    #[inline(always)]
+    pub fn mode(&mut self, mode: ArpMode) {
+        self.mode = mode;
+    }
+
+    /// Change the number of octaves the held notes are repeated over (at
+    /// least 1).
+    #[inline(always)]
+    pub fn octaves(&mut self, octaves: u8) {
+        self.octaves = octaves.max(1);
+    }
+
+    /// Change how long each note is held, as a fraction of a step - range:
+    /// 0~1 (1.0 to hold until the next step).
+    #[inline(always)]
+    pub fn gate(&mut self, gate: f64) {
+        self.gate = gate.clamp(0.0, 1.0);
+    }
+
+    /// Change the seed for the [`ArpMode::Random`] order (every arpeggiator
+    /// starts with the same seed).
+    #[inline(always)]
+    pub fn seed(&mut self, seed: u64) {
+        self.white = White::with_seed(seed);
+    }
+
+    /// Hold down a note, adding it to the arpeggio.  The arpeggio starts
+    /// right away when it's the only note held.
+    pub fn note_on(&mut self, note: Note, velocity: Velocity) {
+        self.held.retain(|(held, _)| *held != note);
+        let index = self.held.iter().take_while(|(held, _)| *held < note);
+        let index = index.count();
+        self.held.insert(index, (note, velocity));
+    }
+
+    /// Let go of a note, removing it from the arpeggio.  The arpeggio stops
+    /// when no notes are held.
+    pub fn note_off(&mut self, note: Note) {
+        self.held.retain(|(held, _)| *held != note);
+        if self.held.is_empty() {
+            self.stop();
+        }
+    }
+
+    /// Change the pitch bend - range: -1~1
+    #[inline(always)]
+    pub fn bend(&mut self, bend: f64) {
+        self.voice.bend(bend);
+    }
+
+    /// Let go of all notes.
+    #[inline(always)]
+    pub fn release_all(&mut self) {
+        self.held.clear();
+        self.stop();
+    }
+
+    /// Returns true when no notes are held and the voice is silent.
+    #[inline(always)]
+    pub fn is_idle(&self) -> bool {
+        self.held.is_empty() && self.voice.is_idle()
+    }
+
+    /// Generate the next sample, advancing at the arpeggiator's rate.
+    #[inline(always)]
+    pub fn gen(&mut self, fc: Fc) -> Signal {
+        if !self.held.is_empty() {
+            if self.step.is_none() || self.phase >= 1.0 {
+                self.phase = self.phase.fract();
+                self.advance();
+            }
+            self.end_gate();
+            self.phase += self.rate / self.sample_rate;
+        }
+        self.voice.gen(fc)
+    }
+
+    /// Generate the next sample, playing one step per `division` of the
+    /// `transport`.  The playing note is released while the transport is
+    /// stopped.
+    #[inline(always)]
+    pub fn sync(
+        &mut self,
+        fc: Fc,
+        transport: &Transport,
+        division: Division,
+    ) -> Signal {
+        if !transport.is_playing() {
+            self.release();
+        } else if !self.held.is_empty() {
+            let tick = transport.step(division);
+            if self.tick != Some(tick) {
+                self.tick = Some(tick);
+                self.advance();
+            }
+            self.phase = transport.phase(division);
+            self.end_gate();
+        }
+        self.voice.gen(fc)
+    }
+
+    /// Release the note at the end of the gate.
+    #[inline(always)]
+    fn end_gate(&mut self) {
+        if self.phase >= self.gate && self.gate < 1.0 {
+            self.release();
+        }
+    }
+
+    /// Stop the arpeggio, so that it starts over at the next note.
+    fn stop(&mut self) {
+        self.release();
+        self.step = None;
+        self.phase = 0.0;
+        self.tick = None;
+    }
+
+    /// Release the playing note.
+    fn release(&mut self) {
+        if let Some(note) = self.playing.take() {
+            self.voice.note_off(note);
+        }
+    }
+
+    /// Play the next note of the arpeggio.
+    fn advance(&mut self) {
+        let step = self.step.map_or(0, |step| step + 1);
+        let len = self.held.len() * usize::from(self.octaves);
+        let index = match self.mode {
+            ArpMode::Up => step % len,
+            ArpMode::Down => len - 1 - step % len,
+            ArpMode::UpDown => {
+                let period = (2 * len).saturating_sub(2).max(1);
+                let index = step % period;
+                if index < len {
+                    index
+                } else {
+                    period - index
+                }
+            }
+            ArpMode::Random => {
+                let random = (f64::from(self.white.noise()) + 1.0) * 0.5;
+                ((random * len as f64) as usize).min(len - 1)
+            }
+        };
+        let (note, velocity) = self.held[index % self.held.len()];
+        let octave = (index / self.held.len()) as f64;
+        let note = Note::new(note.number() + 12.0 * octave);
+        self.release();
+        self.voice.note_on(note, velocity);
+        self.playing = Some(note);
+        self.step = Some(step);
+    }
+}
+
+impl<T: Voice> Voice for Arpeggiator<T> {
+    #[inline(always)]
+    fn note_on(&mut self, note: Note, velocity: Velocity) {
+        Arpeggiator::note_on(self, note, velocity);
+    }
+
+    #[inline(always)]
+    fn note_off(&mut self, note: Note) {
+        Arpeggiator::note_off(self, note);
+    }
+
+    #[inline(always)]
+    fn is_idle(&self) -> bool {
+        Arpeggiator::is_idle(self)
+    }
+
+    #[inline(always)]
+    fn gen(&mut self, fc: Fc) -> Signal {
+        Arpeggiator::gen(self, fc)
+    }
+
+    #[inline(always)]
+    fn bend(&mut self, bend: f64) {
+        Arpeggiator::bend(self, bend);
+    }
+}
@@ -0,0 +1,96 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{curve::curve, sig::Signal};
+
+/// Automation lane.  A breakpoint curve for a parameter over the absolute
+/// time of a render.  Call [`gen()`](Automation::gen) once per sample for
+/// sample-accurate automation.
+#[derive(Clone, Debug)]
+pub struct Automation {
+    sample_rate: f64,
+    // Time in seconds, value and curvature of each point, sorted by time
+    points: Vec<(f64, f64, f64)>,
+    // Current sample
+    sample: u64,
+    // Index of the next point after the current time
+    cursor: usize,
+}
+
+impl Automation {
+    /// Create a new automation lane with no points (always outputs 0).
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        Self {
+            sample_rate: sample_rate.into(),
+            points: Vec::new(),
+            sample: 0,
+            cursor: 0,
+        }
+    }
+
+    /// Add a point to the lane.
+    ///
+    /// - `time`: Time in seconds from the start of the render
+    /// - `value`: Value of the parameter at `time`
+    /// - `curvature`: Shape of the segment leading to the point (0.0 for
+    ///   linear, positive to start slow and end fast, negative to start fast
+    ///   and end slow)
+    pub fn point(&mut self, time: f64, value: f64, curvature: f64) {
+        let index = self.points.partition_point(|p| p.0 <= time);
+        self.points.insert(index, (time, value, curvature));
+        self.seek(self.sample as f64 / self.sample_rate);
+    }
+
+    /// Add a point to the lane at a time in beats, at `bpm` beats per minute.
+    pub fn point_beat(&mut self, beat: f64, bpm: f64, value: f64, curve: f64) {
+        self.point(beat * 60.0 / bpm, value, curve);
+    }
+
+    /// Jump to a time in seconds from the start of the render.
+    pub fn seek(&mut self, time: f64) {
+        self.sample = (time * self.sample_rate).round().max(0.0) as u64;
+        self.cursor = self.points.partition_point(|p| p.0 <= time);
+    }
+
+    /// Get the value of the lane at a time in seconds.
+    pub fn value(&self, time: f64) -> f64 {
+        let index = self.points.partition_point(|p| p.0 <= time);
+        self.interpolate(index, time)
+    }
+
+    /// Generate the value for the next sample.
+    #[inline(always)]
+    pub fn gen(&mut self) -> Signal {
+        let time = self.sample as f64 / self.sample_rate;
+        while self.cursor < self.points.len()
+            && self.points[self.cursor].0 <= time
+        {
+            self.cursor += 1;
+        }
+        self.sample += 1;
+        self.interpolate(self.cursor, time).into()
+    }
+
+    // Interpolate between the points before and at `index`.
+    #[inline(always)]
+    fn interpolate(&self, index: usize, time: f64) -> f64 {
+        match (index.checked_sub(1), self.points.get(index)) {
+            (None, None) => 0.0,
+            (None, Some(next)) => next.1,
+            (Some(prev), None) => self.points[prev].1,
+            (Some(prev), Some(next)) => {
+                let prev = self.points[prev];
+                let t = (time - prev.0) / (next.0 - prev.0);
+                prev.1 + (next.1 - prev.1) * curve(t, next.2)
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::smoothed::Smoothed;
+
+/// Time in seconds to smooth out bend changes (MIDI pitch bend arrives in
+/// steps, which would otherwise be heard as zipper noise).
+const SMOOTH: f64 = 0.005;
+
+/// Pitch bend for a [`Voice`](crate::Voice).  Turns a bend amount (from
+/// [`Voice::bend()`](crate::Voice::bend), or the `bend` of an
+/// [`Expression`](crate::Expression) under MPE) into a smoothed frequency
+/// multiplier, with a configurable range in semitones.
+///
+/// ```rust
+/// use twang::{Note, PitchBend};
+///
+/// let mut bend = PitchBend::new(48_000, 2.0);
+/// // Bend all the way up, a whole step
+/// bend.set(1.0);
+/// let freq = Note::from_midi(69).freq() * bend.gen();
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct PitchBend {
+    // Bend in semitones
+    semitones: Smoothed<f64>,
+    range: f64,
+    bend: f64,
+}
+
+impl PitchBend {
+    /// Create a new centered pitch bend.
+    ///
+    /// - `range`: Number of semitones to bend at full bend (usually 2, or 48
+    ///   for per-note bend under MPE)
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R, range: f64) -> Self {
+        Self {
+            semitones: Smoothed::new(sample_rate, SMOOTH, 0.0),
+            range,
+            bend: 0.0,
+        }
+    }
+
+    /// Change the range in semitones.
+    #[inline(always)]
+    pub fn range(&mut self, range: f64) {
+        self.range = range;
+        self.semitones.set(self.bend * self.range);
+    }
+
+    /// Change the bend - range: -1~1 (0 for centered)
+    #[inline(always)]
+    pub fn set(&mut self, bend: f64) {
+        self.bend = bend.clamp(-1.0, 1.0);
+        self.semitones.set(self.bend * self.range);
+    }
+
+    /// Get the bend in semitones being ramped toward.
+    #[inline(always)]
+    pub fn semitones(&self) -> f64 {
+        self.semitones.target()
+    }
+
+    /// Generate the next frequency multiplier.
+    #[inline(always)]
+    pub fn gen(&mut self) -> f64 {
+        (self.semitones.gen() / 12.0).exp2()
+    }
+}
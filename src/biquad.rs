@@ -0,0 +1,165 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+use std::f64::consts::TAU;
+
+/// Frequency response of a [`Biquad`] filter.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BiquadMode {
+    /// Pass frequencies below the cutoff.
+    Lowpass,
+    /// Pass frequencies above the cutoff.
+    Highpass,
+    /// Pass frequencies around the cutoff.
+    Bandpass,
+    /// Remove frequencies around the cutoff.
+    Notch,
+    /// Boost or cut (gain in dB) frequencies around the cutoff.
+    Peak(f64),
+    /// Boost or cut (gain in dB) frequencies below the cutoff.
+    LowShelf(f64),
+    /// Boost or cut (gain in dB) frequencies above the cutoff.
+    HighShelf(f64),
+}
+
+/// Biquad (second-order) filter, using coefficients from the
+/// [Audio EQ Cookbook](https://www.w3.org/TR/audio-eq-cookbook/).
+#[derive(Copy, Clone, Debug)]
+pub struct Biquad {
+    mode: BiquadMode,
+    sample_rate: f64,
+    // Last cutoff and Q, to only recalculate coefficients when changed
+    cutoff: f64,
+    q: f64,
+    // Normalized coefficients
+    b: [f64; 3],
+    a: [f64; 2],
+    // Transposed direct form II state
+    z: [f64; 2],
+}
+
+impl Biquad {
+    /// Create a new biquad filter.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R, mode: BiquadMode) -> Self {
+        Self {
+            mode,
+            sample_rate: sample_rate.into(),
+            cutoff: f64::NAN,
+            q: f64::NAN,
+            b: [1.0, 0.0, 0.0],
+            a: [0.0, 0.0],
+            z: [0.0, 0.0],
+        }
+    }
+
+    /// Change the frequency response of the filter.
+    #[inline(always)]
+    pub fn mode(&mut self, mode: BiquadMode) {
+        self.mode = mode;
+        self.cutoff = f64::NAN;
+    }
+
+    /// Filter the next sample of a signal.  Coefficients are recalculated
+    /// whenever the cutoff or Q changes, so for audio-rate cutoff modulation
+    /// [`Svf`](crate::Svf) is cheaper.
+    ///
+    /// - `cutoff`: The cutoff (or center) frequency in Hz
+    /// - `q`: The quality factor (0.707 for no resonance)
+    #[inline(always)]
+    pub fn filter<C: Into<Signal>>(
+        &mut self,
+        input: Signal,
+        cutoff: C,
+        q: f64,
+    ) -> Signal {
+        let cutoff = f64::from(cutoff.into());
+        if cutoff != self.cutoff || q != self.q {
+            self.update(cutoff, q);
+        }
+        let x = f64::from(input);
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[0] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[1] * y;
+        y.into()
+    }
+
+    /// Recalculate the coefficients.
+    fn update(&mut self, cutoff: f64, q: f64) {
+        self.cutoff = cutoff;
+        self.q = q;
+        // Keep the filter stable under modulation.
+        let cutoff = cutoff.clamp(0.0, self.sample_rate * 0.49);
+        let w = TAU * cutoff / self.sample_rate;
+        let (sin, cos) = w.sin_cos();
+        let alpha = sin / (2.0 * q.max(0.01));
+        let (b, a) = match self.mode {
+            BiquadMode::Lowpass => (
+                [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+                [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+            ),
+            BiquadMode::Highpass => (
+                [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+                [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+            ),
+            BiquadMode::Bandpass => {
+                ([alpha, 0.0, -alpha], [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+            }
+            BiquadMode::Notch => (
+                [1.0, -2.0 * cos, 1.0],
+                [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+            ),
+            BiquadMode::Peak(gain) => {
+                let amp = 10.0f64.powf(gain / 40.0);
+                (
+                    [1.0 + alpha * amp, -2.0 * cos, 1.0 - alpha * amp],
+                    [1.0 + alpha / amp, -2.0 * cos, 1.0 - alpha / amp],
+                )
+            }
+            BiquadMode::LowShelf(gain) => {
+                let amp = 10.0f64.powf(gain / 40.0);
+                let beta = 2.0 * amp.sqrt() * alpha;
+                let (p, m) = (amp + 1.0, amp - 1.0);
+                (
+                    [
+                        amp * (p - m * cos + beta),
+                        2.0 * amp * (m - p * cos),
+                        amp * (p - m * cos - beta),
+                    ],
+                    [
+                        p + m * cos + beta,
+                        -2.0 * (m + p * cos),
+                        p + m * cos - beta,
+                    ],
+                )
+            }
+            BiquadMode::HighShelf(gain) => {
+                let amp = 10.0f64.powf(gain / 40.0);
+                let beta = 2.0 * amp.sqrt() * alpha;
+                let (p, m) = (amp + 1.0, amp - 1.0);
+                (
+                    [
+                        amp * (p + m * cos + beta),
+                        -2.0 * amp * (m + p * cos),
+                        amp * (p + m * cos - beta),
+                    ],
+                    [
+                        p - m * cos + beta,
+                        2.0 * (m - p * cos),
+                        p - m * cos - beta,
+                    ],
+                )
+            }
+        };
+        self.b = [b[0] / a[0], b[1] / a[0], b[2] / a[0]];
+        self.a = [a[1] / a[0], a[2] / a[0]];
+    }
+}
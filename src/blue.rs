@@ -0,0 +1,39 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{pink::Pink, sig::Signal};
+
+/// Gain to keep the output level similar to pink noise.
+const GAIN: f64 = 1.75;
+
+/// Blue Noise Generator using differentiated pink noise.
+#[derive(Default, Clone, Debug)]
+#[allow(missing_copy_implementations)]
+pub struct Blue {
+    pink: Pink,
+    last: f64,
+}
+
+impl Blue {
+    /// Create a new Blue Noise Sampler.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get next sample of blue noise.
+    #[inline(always)]
+    pub fn noise(&mut self) -> Signal {
+        let noise = f64::from(self.pink.noise());
+        let out = (noise - self.last) * GAIN;
+        self.last = noise;
+        Signal::from(out)
+    }
+}
@@ -0,0 +1,40 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{sig::Signal, white::White};
+
+/// Leak of the integrator (keeps the output from drifting away from zero).
+const LEAK: f64 = 0.998;
+/// Gain applied to the white noise, so the output has a standard deviation of
+/// about ⅓.
+const STEP: f64 = 0.0365;
+
+/// Brown (Red / Brownian) Noise Generator using leaky integrated white noise.
+#[derive(Default, Clone, Debug)]
+#[allow(missing_copy_implementations)]
+pub struct Brown {
+    white: White,
+    accu: f64,
+}
+
+impl Brown {
+    /// Create a new Brown Noise Sampler.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get next sample of brown noise.
+    #[inline(always)]
+    pub fn noise(&mut self) -> Signal {
+        self.accu = self.accu * LEAK + f64::from(self.white.noise()) * STEP;
+        Signal::from(self.accu)
+    }
+}
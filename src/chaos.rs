@@ -0,0 +1,106 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+
+/// Lorenz attractor chaotic signal source.
+#[derive(Copy, Clone, Debug)]
+pub struct Lorenz {
+    x: f64,
+    y: f64,
+    z: f64,
+    sample_rate: f64,
+}
+
+impl Lorenz {
+    /// Create a new Lorenz attractor.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        Self {
+            x: 0.1,
+            y: 0.0,
+            z: 0.0,
+            sample_rate: sample_rate.into(),
+        }
+    }
+
+    /// Get the next sample.
+    ///
+    /// - `rate`: Speed of the attractor (around 1 for slow drones, 100+ for
+    ///   audio rate)
+    /// - `chaos`: The rho parameter of the attractor (28.0 is classic chaos,
+    ///   values under 24.74 settle down)
+    #[inline(always)]
+    pub fn gen(&mut self, rate: f64, chaos: f64) -> Signal {
+        const SIGMA: f64 = 10.0;
+        const BETA: f64 = 8.0 / 3.0;
+
+        let dt = (rate / self.sample_rate).min(0.01);
+        let dx = SIGMA * (self.y - self.x);
+        let dy = self.x * (chaos - self.z) - self.y;
+        let dz = self.x * self.y - BETA * self.z;
+        self.x += dx * dt;
+        self.y += dy * dt;
+        self.z += dz * dt;
+        (self.x / 25.0).into()
+    }
+}
+
+/// Starting value of the logistic map (0.5 would go to 1.0 and then get stuck
+/// at 0.0 when fully chaotic).
+const START: f64 = 0.3;
+
+/// Logistic map chaotic signal source.
+///
+/// ```rust
+/// use twang::Logistic;
+///
+/// let mut logistic = Logistic::new(48_000);
+/// let samples: Vec<f64> =
+///     (0..8).map(|_| logistic.gen(48_000.0, 4.0).into()).collect();
+/// assert!(samples.windows(2).all(|pair| pair[0] != pair[1]));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Logistic {
+    x: f64,
+    phase: f64,
+    sample_rate: f64,
+}
+
+impl Logistic {
+    /// Create a new logistic map.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        Self {
+            x: START,
+            phase: 0.0,
+            sample_rate: sample_rate.into(),
+        }
+    }
+
+    /// Get the next sample.
+    ///
+    /// - `rate`: Number of iterations of the map per second (Hz)
+    /// - `chaos`: The r parameter of the map - range: 0~4 (values over about
+    ///   3.57 are chaotic)
+    #[inline(always)]
+    pub fn gen(&mut self, rate: f64, chaos: f64) -> Signal {
+        self.phase += rate / self.sample_rate;
+        while self.phase >= 1.0 {
+            self.phase -= 1.0;
+            self.x = chaos.clamp(0.0, 4.0) * self.x * (1.0 - self.x);
+            // Keep from getting stuck at zero
+            if self.x <= 0.0 || self.x >= 1.0 {
+                self.x = START;
+            }
+        }
+        (self.x * 2.0 - 1.0).into()
+    }
+}
@@ -0,0 +1,81 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+
+/// Duty cycle of a chiptune (NES / Game Boy style) pulse wave.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Duty {
+    /// 12.5% duty cycle
+    Eighth,
+    /// 25% duty cycle
+    Quarter,
+    /// 50% duty cycle (square wave)
+    Half,
+    /// 75% duty cycle
+    ThreeQuarters,
+}
+
+impl Duty {
+    /// Get the 8-step sequence for the duty cycle.
+    #[inline(always)]
+    pub(crate) fn sequence(self) -> u8 {
+        match self {
+            Duty::Eighth => 0b0100_0000,
+            Duty::Quarter => 0b0110_0000,
+            Duty::Half => 0b0111_1000,
+            Duty::ThreeQuarters => 0b1001_1111,
+        }
+    }
+}
+
+/// Chiptune (NES / Game Boy style) noise generator using a 15-bit LFSR
+/// (Linear-Feedback Shift Register).
+#[derive(Copy, Clone, Debug)]
+pub struct Lfsr {
+    register: u16,
+    short: bool,
+    phase: f64,
+    sample_rate: f64,
+}
+
+impl Lfsr {
+    /// Create a new LFSR noise generator.
+    ///
+    /// - `short`: Use short mode (metallic, periodic noise) rather than long
+    ///   mode (hiss)
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R, short: bool) -> Self {
+        Self {
+            register: 1,
+            short,
+            phase: 0.0,
+            sample_rate: sample_rate.into(),
+        }
+    }
+
+    /// Get the next sample of noise, with the shift register clocked at
+    /// frequency `freq`.
+    #[inline(always)]
+    pub fn noise(&mut self, freq: f64) -> Signal {
+        self.phase += freq / self.sample_rate;
+        while self.phase >= 1.0 {
+            self.phase -= 1.0;
+            let tap = if self.short { 6 } else { 1 };
+            let feedback = (self.register ^ (self.register >> tap)) & 1;
+            self.register = (self.register >> 1) | (feedback << 14);
+        }
+        if self.register & 1 == 0 {
+            1.0.into()
+        } else {
+            (-1.0).into()
+        }
+    }
+}
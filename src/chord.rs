@@ -0,0 +1,178 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{note::Note, poly::Voice, velocity::Velocity};
+
+/// Kind of [`Chord`], by the intervals above the root.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChordQuality {
+    /// Major triad (1 3 5)
+    Major,
+    /// Minor triad (1 ♭3 5)
+    Minor,
+    /// Diminished triad (1 ♭3 ♭5)
+    Diminished,
+    /// Augmented triad (1 3 ♯5)
+    Augmented,
+    /// Suspended second (1 2 5)
+    Sus2,
+    /// Suspended fourth (1 4 5)
+    Sus4,
+    /// Major seventh (1 3 5 7)
+    Major7,
+    /// Dominant seventh (1 3 5 ♭7)
+    Dominant7,
+    /// Minor seventh (1 ♭3 5 ♭7)
+    Minor7,
+    /// Minor major seventh (1 ♭3 5 7)
+    MinorMajor7,
+    /// Half-diminished seventh (1 ♭3 ♭5 ♭7)
+    HalfDiminished7,
+    /// Diminished seventh (1 ♭3 ♭5 𝄫7)
+    Diminished7,
+}
+
+impl ChordQuality {
+    /// Get the intervals above the root in semitones (including the root).
+    pub fn intervals(self) -> &'static [u8] {
+        use ChordQuality::*;
+        match self {
+            Major => &[0, 4, 7],
+            Minor => &[0, 3, 7],
+            Diminished => &[0, 3, 6],
+            Augmented => &[0, 4, 8],
+            Sus2 => &[0, 2, 7],
+            Sus4 => &[0, 5, 7],
+            Major7 => &[0, 4, 7, 11],
+            Dominant7 => &[0, 4, 7, 10],
+            Minor7 => &[0, 3, 7, 10],
+            MinorMajor7 => &[0, 3, 7, 11],
+            HalfDiminished7 => &[0, 3, 6, 10],
+            Diminished7 => &[0, 3, 6, 9],
+        }
+    }
+}
+
+/// A chord, as a set of notes from lowest to highest.  Built from a root and
+/// a [`ChordQuality`], then revoiced with inversions, drop voicings and
+/// extensions, and played on a [`Voice`] such as a [`Poly`](crate::Poly).
+///
+/// ```rust
+/// use twang::{Chord, ChordQuality, Note};
+///
+/// // C major seventh in first inversion
+/// let chord = Chord::new(Note::from_midi(60), ChordQuality::Major7).invert(1);
+/// let notes: Vec<f64> = chord.notes().iter().map(|n| n.number()).collect();
+/// assert_eq!(notes, [64.0, 67.0, 71.0, 72.0]);
+/// // Drop 2 voicing
+/// let chord = Chord::new(Note::from_midi(60), ChordQuality::Major7).drop(2);
+/// let notes: Vec<f64> = chord.notes().iter().map(|n| n.number()).collect();
+/// assert_eq!(notes, [55.0, 60.0, 64.0, 71.0]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chord(Vec<Note>);
+
+impl Chord {
+    /// Create a new chord in root position.
+    pub fn new(root: Note, quality: ChordQuality) -> Self {
+        let root = root.number();
+        let notes = quality.intervals().iter();
+        Self(notes.map(|i| Note::new(root + f64::from(*i))).collect())
+    }
+
+    /// Create a new chord from any notes.
+    pub fn from_notes(notes: &[Note]) -> Self {
+        let mut chord = Self(notes.to_vec());
+        chord.sort();
+        chord
+    }
+
+    /// Get the notes, from lowest to highest.
+    #[inline(always)]
+    pub fn notes(&self) -> &[Note] {
+        &self.0
+    }
+
+    /// Get the frequencies of the notes in Hz, from lowest to highest.
+    pub fn freqs(&self) -> Vec<f64> {
+        self.0.iter().map(|note| note.freq()).collect()
+    }
+
+    /// Invert the chord, moving the lowest note up an octave `inversion`
+    /// times (1 for first inversion, 2 for second inversion, …).
+    pub fn invert(mut self, inversion: usize) -> Self {
+        for _ in 0..inversion {
+            if let Some(lowest) = self.0.first_mut() {
+                *lowest = Note::new(lowest.number() + 12.0);
+            }
+            self.sort();
+        }
+        self
+    }
+
+    /// Drop voicing: move the `n`th highest note down an octave (2 for a
+    /// "drop 2" voicing, 3 for "drop 3").
+    pub fn drop(mut self, n: usize) -> Self {
+        let len = self.0.len();
+        if n >= 1 && n <= len {
+            let note = &mut self.0[len - n];
+            *note = Note::new(note.number() - 12.0);
+            self.sort();
+        }
+        self
+    }
+
+    /// Open voicing: move every second note (from the lowest) up an octave,
+    /// spreading the chord over a wider range.
+    pub fn spread(mut self) -> Self {
+        for note in self.0.iter_mut().skip(1).step_by(2) {
+            *note = Note::new(note.number() + 12.0);
+        }
+        self.sort();
+        self
+    }
+
+    /// Add a note `semitones` above the lowest note (for extensions, such as
+    /// 14 for a ninth, or for doubling the root with 12).
+    pub fn extend(mut self, semitones: f64) -> Self {
+        if let Some(lowest) = self.0.first() {
+            self.0.push(Note::new(lowest.number() + semitones));
+            self.sort();
+        }
+        self
+    }
+
+    /// Move the whole chord up (or down, if negative) by `semitones`.
+    pub fn transpose(mut self, semitones: f64) -> Self {
+        for note in self.0.iter_mut() {
+            *note = Note::new(note.number() + semitones);
+        }
+        self
+    }
+
+    /// Start playing every note of the chord on a voice.
+    pub fn note_on<V: Voice>(&self, voice: &mut V, velocity: Velocity) {
+        for note in self.0.iter() {
+            voice.note_on(*note, velocity);
+        }
+    }
+
+    /// Release every note of the chord on a voice.
+    pub fn note_off<V: Voice>(&self, voice: &mut V) {
+        for note in self.0.iter() {
+            voice.note_off(*note);
+        }
+    }
+
+    /// Sort the notes from lowest to highest.
+    fn sort(&mut self) {
+        self.0.sort_by(|a, b| a.partial_cmp(b).expect("NaN note"));
+    }
+}
@@ -0,0 +1,72 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{line::Line, sig::Signal};
+use std::f64::consts::TAU;
+
+/// Center delay time of each chorus voice in seconds.
+const CENTER: f64 = 0.012;
+/// Maximum distance from the center delay time in seconds.
+const SWEEP: f64 = 0.008;
+
+/// Chorus effect.  A multi-tap delay, where each tap (voice) has it's delay
+/// time modulated by an LFO, evenly offset in phase from the other voices.
+#[derive(Clone, Debug)]
+pub struct Chorus {
+    line: Line,
+    sample_rate: f64,
+    voices: usize,
+    // LFO phase (0 to 1)
+    phase: f64,
+}
+
+impl Chorus {
+    /// Create a new chorus effect.
+    ///
+    /// - `voices`: Number of modulated delay taps - range: 2~4
+    pub fn new<R: Into<f64>>(sample_rate: R, voices: usize) -> Self {
+        assert!((2..=4).contains(&voices), "Chorus needs 2 to 4 voices");
+        let sample_rate = sample_rate.into();
+        let max = ((CENTER + SWEEP) * sample_rate).ceil() as usize + 2;
+        Self {
+            line: Line::new(max),
+            sample_rate,
+            voices,
+            phase: 0.0,
+        }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `rate`: LFO frequency in Hz
+    /// - `depth`: Amount of delay time modulation - range: 0~1
+    /// - `mix`: Wet/dry mix - range: 0~1 (0.0 for dry only, 1.0 for wet only)
+    #[inline(always)]
+    pub fn process(
+        &mut self,
+        input: Signal,
+        rate: f64,
+        depth: f64,
+        mix: f64,
+    ) -> Signal {
+        let x = f64::from(input);
+        self.phase = (self.phase + rate / self.sample_rate).fract();
+        self.line.push(x);
+        let sweep = SWEEP * depth.clamp(0.0, 1.0);
+        let mut wet = 0.0;
+        for voice in 0..self.voices {
+            let offset = voice as f64 / self.voices as f64;
+            let lfo = (TAU * (self.phase + offset)).sin();
+            wet += self.line.read((CENTER + sweep * lfo) * self.sample_rate);
+        }
+        wet /= self.voices as f64;
+        (x * (1.0 - mix) + wet * mix).into()
+    }
+}
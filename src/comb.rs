@@ -0,0 +1,74 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{line::Line, sig::Signal};
+
+/// Topology of a [`Comb`] filter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CombMode {
+    /// Mix the delayed input with the input (notches).
+    Feedforward,
+    /// Mix the delayed output with the input (resonant peaks).
+    Feedback,
+}
+
+/// Comb filter.  A building block for reverbs, resonators, and flanging.
+#[derive(Clone, Debug)]
+pub struct Comb {
+    line: Line,
+    mode: CombMode,
+    sample_rate: f64,
+}
+
+impl Comb {
+    /// Create a new comb filter.
+    ///
+    /// - `max_delay`: The maximum delay in samples
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        mode: CombMode,
+        max_delay: usize,
+    ) -> Self {
+        Self {
+            line: Line::new(max_delay),
+            mode,
+            sample_rate: sample_rate.into(),
+        }
+    }
+
+    /// Filter the next sample of a signal.
+    ///
+    /// - `delay`: The delay in samples (may be fractional)
+    /// - `gain`: Gain of the delayed signal (keep under 1 for feedback)
+    #[inline(always)]
+    pub fn filter(&mut self, input: Signal, delay: f64, gain: f64) -> Signal {
+        let x = f64::from(input);
+        let y = x + gain * self.line.read(delay);
+        self.line.push(match self.mode {
+            CombMode::Feedforward => x,
+            CombMode::Feedback => y,
+        });
+        y.into()
+    }
+
+    /// Filter the next sample of a signal, with the delay tuned so that the
+    /// comb's fundamental is at frequency `freq` in Hz.
+    #[inline(always)]
+    pub fn filter_freq(
+        &mut self,
+        input: Signal,
+        freq: f64,
+        gain: f64,
+    ) -> Signal {
+        let delay = self.sample_rate / freq;
+        self.filter(input, delay, gain)
+    }
+}
@@ -0,0 +1,134 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+
+/// Convert a linear gain to decibels.
+#[inline(always)]
+pub(crate) fn to_db(gain: f64) -> f64 {
+    20.0 * gain.max(1e-10).log10()
+}
+
+/// Convert decibels to a linear gain.
+#[inline(always)]
+pub(crate) fn from_db(db: f64) -> f64 {
+    10.0f64.powf(db / 20.0)
+}
+
+/// Feed-forward dynamic range compressor.  Reduces the gain of a signal by
+/// `ratio` while it is above `threshold`.
+#[derive(Copy, Clone, Debug)]
+pub struct Compressor {
+    sample_rate: f64,
+    attack: f64,
+    release: f64,
+    // Smoothed gain reduction in dB
+    reduction: f64,
+}
+
+impl Compressor {
+    /// Create a new compressor.
+    ///
+    /// - `attack`: Time in seconds to react to a rising level
+    /// - `release`: Time in seconds to recover from a falling level
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        attack: f64,
+        release: f64,
+    ) -> Self {
+        let mut compressor = Self {
+            sample_rate: sample_rate.into(),
+            attack: 0.0,
+            release: 0.0,
+            reduction: 0.0,
+        };
+        compressor.time(attack, release);
+        compressor
+    }
+
+    /// Change the attack and release times in seconds.
+    pub fn time(&mut self, attack: f64, release: f64) {
+        let sample_rate = self.sample_rate;
+        let coeff = |time: f64| {
+            if time > 0.0 {
+                (-1.0 / (time * sample_rate)).exp()
+            } else {
+                0.0
+            }
+        };
+        self.attack = coeff(attack);
+        self.release = coeff(release);
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `threshold`: Level in dB above which gain is reduced
+    /// - `ratio`: Input to output level ratio above the threshold - range: 1+
+    /// - `knee`: Width in dB of the soft transition around the threshold
+    /// - `makeup`: Gain in dB applied after compression
+    #[inline(always)]
+    pub fn process(
+        &mut self,
+        input: Signal,
+        threshold: f64,
+        ratio: f64,
+        knee: f64,
+        makeup: f64,
+    ) -> Signal {
+        self.sidechain(input, input, threshold, ratio, knee, makeup)
+    }
+
+    /// Process the next sample of a signal, with the gain reduction driven by
+    /// the level of a separate detector (sidechain) signal `key` instead of
+    /// `input`.  Use for ducking, or pumping driven by a kick drum.
+    /// Parameters are the same as [`process()`](Self::process).
+    #[inline(always)]
+    pub fn sidechain(
+        &mut self,
+        input: Signal,
+        key: Signal,
+        threshold: f64,
+        ratio: f64,
+        knee: f64,
+        makeup: f64,
+    ) -> Signal {
+        let gain = self.gain(key, threshold, ratio, knee);
+        input.gain(gain * from_db(makeup))
+    }
+
+    /// Calculate the next linear gain from the level of `key`.
+    #[inline(always)]
+    pub(crate) fn gain(
+        &mut self,
+        key: Signal,
+        threshold: f64,
+        ratio: f64,
+        knee: f64,
+    ) -> f64 {
+        let level = to_db(f64::from(key).abs());
+        let over = level - threshold;
+        let slope = 1.0 - ratio.max(1.0).recip();
+        // Target gain reduction (positive dB), with a quadratic soft knee
+        let target = if 2.0 * over <= -knee {
+            0.0
+        } else if 2.0 * over < knee {
+            slope * (over + knee * 0.5).powi(2) / (2.0 * knee)
+        } else {
+            slope * over
+        };
+        let coeff = if target > self.reduction {
+            self.attack
+        } else {
+            self.release
+        };
+        self.reduction = target + coeff * (self.reduction - target);
+        from_db(-self.reduction)
+    }
+}
@@ -0,0 +1,157 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    fft::{Complex, Fft},
+    sig::Signal,
+};
+
+/// Convolution reverb.  Renders a signal through an impulse response (for
+/// example, one recorded in a real space) using uniformly partitioned FFT
+/// convolution.
+#[derive(Clone, Debug)]
+pub struct Convolution {
+    fft: Fft,
+    block: usize,
+    // Impulse response taps convolved directly, for zero latency
+    direct: Vec<f64>,
+    // Spectra of each impulse response partition
+    partitions: Vec<Vec<Complex>>,
+    // Spectra of the most recent input blocks (ring buffer)
+    spectra: Vec<Vec<Complex>>,
+    // Index of the newest spectrum in `spectra`
+    newest: usize,
+    // The last two blocks of input
+    input: Vec<f64>,
+    // Output block currently being played
+    output: Vec<f64>,
+    // Position within the current block
+    position: usize,
+    // Recent input for direct convolution (ring buffer)
+    history: Vec<f64>,
+    // Index of the newest sample in `history`
+    write: usize,
+    // FFT scratch space
+    scratch: Vec<Complex>,
+}
+
+impl Convolution {
+    /// Create a new convolution reverb from the samples of an impulse
+    /// response.
+    ///
+    /// - `block`: Partition size in samples (a power of two) - larger is
+    ///   faster, but adds more latency
+    /// - `zero_latency`: If true, the first partition is convolved directly in
+    ///   the time domain, so that there is no latency
+    pub fn new(impulse: &[f64], block: usize, zero_latency: bool) -> Self {
+        assert!(block.is_power_of_two(), "Block size must be a power of two");
+        let fft = Fft::new(block * 2);
+        let (direct, tail) = if zero_latency {
+            let split = block.min(impulse.len());
+            (impulse[..split].to_vec(), &impulse[split..])
+        } else {
+            (Vec::new(), impulse)
+        };
+        let partitions: Vec<Vec<Complex>> = tail
+            .chunks(block)
+            .map(|chunk| {
+                let mut spectrum = vec![Complex::default(); block * 2];
+                for (bin, tap) in spectrum.iter_mut().zip(chunk) {
+                    bin.re = *tap;
+                }
+                fft.forward(&mut spectrum);
+                spectrum
+            })
+            .collect();
+        let spectra =
+            vec![vec![Complex::default(); block * 2]; partitions.len()];
+        Self {
+            history: vec![0.0; direct.len()],
+            write: 0,
+            direct,
+            partitions,
+            spectra,
+            newest: 0,
+            input: vec![0.0; block * 2],
+            output: vec![0.0; block],
+            position: 0,
+            scratch: vec![Complex::default(); block * 2],
+            fft,
+            block,
+        }
+    }
+
+    /// Get the latency of the output in samples.
+    #[inline(always)]
+    pub fn latency(&self) -> usize {
+        if self.direct.is_empty() {
+            self.block
+        } else {
+            0
+        }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `mix`: Wet/dry mix - range: 0~1 (0.0 for dry only, 1.0 for wet only)
+    #[inline(always)]
+    pub fn process(&mut self, input: Signal, mix: f64) -> Signal {
+        let x = f64::from(input);
+        let mut wet = self.output[self.position];
+        // Direct convolution
+        if !self.history.is_empty() {
+            self.write = (self.write + 1) % self.history.len();
+            self.history[self.write] = x;
+            let (newer, older) = self.history.split_at(self.write + 1);
+            let recent = newer.iter().rev().chain(older.iter().rev());
+            for (tap, sample) in self.direct.iter().zip(recent) {
+                wet += tap * sample;
+            }
+        }
+        self.input[self.block + self.position] = x;
+        self.position += 1;
+        if self.position == self.block {
+            self.position = 0;
+            self.convolve_block();
+        }
+        (x * (1.0 - mix) + wet * mix).into()
+    }
+
+    // Convolve the last block of input with all partitions, and shift it.
+    fn convolve_block(&mut self) {
+        if !self.partitions.is_empty() {
+            let count = self.partitions.len();
+            self.newest = (self.newest + 1) % count;
+            let spectrum = &mut self.spectra[self.newest];
+            for (bin, sample) in spectrum.iter_mut().zip(&self.input) {
+                *bin = Complex::new(*sample, 0.0);
+            }
+            self.fft.forward(spectrum);
+            for bin in self.scratch.iter_mut() {
+                *bin = Complex::default();
+            }
+            for (i, partition) in self.partitions.iter().enumerate() {
+                let spectrum = &self.spectra[(self.newest + count - i) % count];
+                for ((out, x), h) in
+                    self.scratch.iter_mut().zip(spectrum).zip(partition)
+                {
+                    *out += *x * *h;
+                }
+            }
+            self.fft.inverse(&mut self.scratch);
+            for (out, y) in
+                self.output.iter_mut().zip(&self.scratch[self.block..])
+            {
+                *out = y.re;
+            }
+        }
+        self.input.copy_within(self.block.., 0);
+    }
+}
@@ -0,0 +1,80 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    biquad::{Biquad, BiquadMode},
+    sig::Signal,
+};
+use std::f64::consts::FRAC_1_SQRT_2;
+
+/// 4th order Linkwitz-Riley lowpass and highpass filters at one frequency.
+#[derive(Copy, Clone, Debug)]
+struct Split {
+    freq: f64,
+    lowpass: [Biquad; 2],
+    highpass: [Biquad; 2],
+}
+
+impl Split {
+    fn new(sample_rate: f64, freq: f64) -> Self {
+        Self {
+            freq,
+            lowpass: [Biquad::new(sample_rate, BiquadMode::Lowpass); 2],
+            highpass: [Biquad::new(sample_rate, BiquadMode::Highpass); 2],
+        }
+    }
+
+    /// Split a signal into (low, high) bands.
+    #[inline(always)]
+    fn split(&mut self, input: Signal) -> (Signal, Signal) {
+        let freq = self.freq;
+        let cascade = |filters: &mut [Biquad; 2]| {
+            filters.iter_mut().fold(input, |signal, filter| {
+                filter.filter(signal, freq, FRAC_1_SQRT_2)
+            })
+        };
+        (cascade(&mut self.lowpass), cascade(&mut self.highpass))
+    }
+}
+
+/// Linkwitz-Riley (4th order) crossover.  Splits a signal into low, mid and
+/// high bands that sum back together with a flat frequency response.
+#[derive(Copy, Clone, Debug)]
+pub struct Crossover {
+    low: Split,
+    high: Split,
+    // Allpass on the low band to keep it in phase with the mid and high bands.
+    compensate: Split,
+}
+
+impl Crossover {
+    /// Create a new crossover.
+    ///
+    /// - `low`: Crossover frequency between the low and mid bands in Hz
+    /// - `high`: Crossover frequency between the mid and high bands in Hz
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R, low: f64, high: f64) -> Self {
+        let sample_rate = sample_rate.into();
+        Self {
+            low: Split::new(sample_rate, low),
+            high: Split::new(sample_rate, high),
+            compensate: Split::new(sample_rate, high),
+        }
+    }
+
+    /// Split the next sample of a signal into (low, mid, high) bands.
+    #[inline(always)]
+    pub fn split(&mut self, input: Signal) -> [Signal; 3] {
+        let (low, rest) = self.low.split(input);
+        let (mid, high) = self.high.split(rest);
+        let (a, b) = self.compensate.split(low);
+        [(f64::from(a) + f64::from(b)).into(), mid, high]
+    }
+}
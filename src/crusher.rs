@@ -0,0 +1,71 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    biquad::{Biquad, BiquadMode},
+    sig::Signal,
+};
+use std::f64::consts::FRAC_1_SQRT_2;
+
+/// Bitcrusher.  Reduces bit depth by quantizing, and reduces sample rate by
+/// holding samples, for lo-fi sounds.
+#[derive(Copy, Clone, Debug)]
+pub struct Crusher {
+    sample_rate: f64,
+    // Lowpass filters applied before rate reduction, if anti-aliased
+    filters: Option<[Biquad; 2]>,
+    // Rate reduction phase (0 to 1)
+    phase: f64,
+    held: f64,
+}
+
+impl Crusher {
+    /// Create a new bitcrusher.
+    ///
+    /// - `anti_alias`: If true, frequencies above half of the reduced sample
+    ///   rate are filtered out before reducing it
+    pub fn new<R: Into<f64>>(sample_rate: R, anti_alias: bool) -> Self {
+        let sample_rate = sample_rate.into();
+        let lowpass = Biquad::new(sample_rate, BiquadMode::Lowpass);
+        Self {
+            sample_rate,
+            filters: if anti_alias { Some([lowpass; 2]) } else { None },
+            phase: 1.0,
+            held: 0.0,
+        }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `bits`: Bit depth to quantize to (may be fractional) - range: 1+.
+    ///   Quantizes to 2^bits levels including 0, like integer PCM (1 bit
+    ///   leaves only -1 and 0)
+    /// - `rate`: Sample rate to reduce to in Hz
+    #[inline(always)]
+    pub fn process(&mut self, input: Signal, bits: f64, rate: f64) -> Signal {
+        let rate = rate.clamp(1.0, self.sample_rate);
+        let input = match self.filters {
+            Some(ref mut filters) => {
+                let cutoff = rate * 0.45;
+                let input = filters[0].filter(input, cutoff, FRAC_1_SQRT_2);
+                filters[1].filter(input, cutoff, FRAC_1_SQRT_2)
+            }
+            None => input,
+        };
+        self.phase += rate / self.sample_rate;
+        if self.phase >= 1.0 {
+            self.phase = self.phase.fract();
+            let steps = (bits.max(1.0) - 1.0).exp2();
+            let level = (f64::from(input) * steps).round();
+            self.held = level.clamp(-steps, steps - 1.0) / steps;
+        }
+        self.held.into()
+    }
+}
@@ -0,0 +1,21 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+/// Bend progress `t` (0 to 1) through a segment by `curvature`.  0 is linear,
+/// positive values start slow and end fast (exponential), and negative values
+/// start fast and end slow (logarithmic).
+#[inline(always)]
+pub(crate) fn curve(t: f64, curvature: f64) -> f64 {
+    if curvature.abs() < 1e-6 {
+        t
+    } else {
+        (curvature * t).exp_m1() / curvature.exp_m1()
+    }
+}
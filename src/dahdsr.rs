@@ -0,0 +1,164 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{curve::curve, retrigger::Retrigger, sig::Signal};
+
+/// Stage of a [`Dahdsr`] envelope.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Stage {
+    Idle,
+    Delay,
+    Attack,
+    Hold,
+    Decay,
+    Sustain,
+    Release,
+}
+
+/// DAHDSR (delay, attack, hold, decay, sustain, release) envelope generator,
+/// with adjustable curvature of the attack, decay and release stages.
+#[derive(Copy, Clone, Debug)]
+pub struct Dahdsr {
+    sample_rate: f64,
+    // Stage times in seconds
+    delay: f64,
+    attack: f64,
+    hold: f64,
+    decay: f64,
+    sustain: f64,
+    release: f64,
+    // Attack, decay and release curvature
+    curves: [f64; 3],
+    retrigger: Retrigger,
+    stage: Stage,
+    // Progress through the current stage (0 to 1), and how much to advance
+    // it each sample
+    t: f64,
+    dt: f64,
+    // Level at the start of the current stage
+    start: f64,
+    level: f64,
+}
+
+impl Dahdsr {
+    /// Create a new DAHDSR envelope, with linear stages.
+    ///
+    /// - `delay`: Time in seconds to wait before the attack
+    /// - `attack`: Time in seconds to rise from 0 to 1
+    /// - `hold`: Time in seconds to stay at 1 before the decay
+    /// - `decay`: Time in seconds to fall from 1 to the sustain level
+    /// - `sustain`: Level held while the note is on - range: 0~1
+    /// - `release`: Time in seconds to fall to 0 after the note is off
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        delay: f64,
+        attack: f64,
+        hold: f64,
+        decay: f64,
+        sustain: f64,
+        release: f64,
+    ) -> Self {
+        Self {
+            sample_rate: sample_rate.into(),
+            delay,
+            attack,
+            hold,
+            decay,
+            sustain: sustain.clamp(0.0, 1.0),
+            release,
+            curves: [0.0; 3],
+            retrigger: Retrigger::default(),
+            stage: Stage::Idle,
+            t: 0.0,
+            dt: 0.0,
+            start: 0.0,
+            level: 0.0,
+        }
+    }
+
+    /// Change the curvature of the attack, decay and release stages.  0 is
+    /// linear, positive values start slow and end fast, and negative values
+    /// start fast and end slow (for example, -4.0 for a natural sounding
+    /// amplitude decay).
+    pub fn curves(&mut self, attack: f64, decay: f64, release: f64) {
+        self.curves = [attack, decay, release];
+    }
+
+    /// Change what happens when a note starts while the envelope is playing.
+    pub fn retrigger(&mut self, retrigger: Retrigger) {
+        self.retrigger = retrigger;
+    }
+
+    /// Start the delay stage.
+    pub fn note_on(&mut self) {
+        match self.retrigger {
+            Retrigger::Always => self.level = 0.0,
+            Retrigger::FromCurrent => {}
+            Retrigger::Legato => {
+                if !matches!(self.stage, Stage::Idle | Stage::Release) {
+                    return;
+                }
+            }
+        }
+        self.enter(Stage::Delay, self.delay);
+    }
+
+    /// Start the release stage.
+    pub fn note_off(&mut self) {
+        if self.stage != Stage::Idle {
+            self.enter(Stage::Release, self.release);
+        }
+    }
+
+    /// Returns true once the release stage has finished (or before the first
+    /// note).
+    #[inline(always)]
+    pub fn is_idle(&self) -> bool {
+        self.stage == Stage::Idle
+    }
+
+    /// Generate the next sample of the envelope.
+    #[inline(always)]
+    pub fn gen(&mut self) -> Signal {
+        let (end, curvature) = match self.stage {
+            Stage::Idle | Stage::Sustain => return self.level.into(),
+            Stage::Delay | Stage::Hold => (self.level, 0.0),
+            Stage::Attack => (1.0, self.curves[0]),
+            Stage::Decay => (self.sustain, self.curves[1]),
+            Stage::Release => (0.0, self.curves[2]),
+        };
+        self.t += self.dt;
+        if self.t >= 1.0 {
+            self.level = end;
+            match self.stage {
+                Stage::Delay => {
+                    let time = self.attack * (1.0 - self.level);
+                    self.enter(Stage::Attack, time)
+                }
+                Stage::Attack => self.enter(Stage::Hold, self.hold),
+                Stage::Hold => self.enter(Stage::Decay, self.decay),
+                Stage::Decay => self.stage = Stage::Sustain,
+                _ => self.stage = Stage::Idle,
+            }
+        } else {
+            self.level =
+                self.start + (end - self.start) * curve(self.t, curvature);
+        }
+        self.level.into()
+    }
+
+    fn enter(&mut self, stage: Stage, time: f64) {
+        self.stage = stage;
+        self.start = self.level;
+        self.t = 0.0;
+        // Zero length stages finish on the next sample
+        self.dt = (time * self.sample_rate).max(1.0).recip();
+    }
+}
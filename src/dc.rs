@@ -0,0 +1,45 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+use std::f64::consts::TAU;
+
+/// Cutoff frequency of the DC blocker in Hz.
+const CUTOFF: f64 = 10.0;
+
+/// DC blocker.  One-pole / one-zero highpass filter that removes DC offset
+/// (introduced by waveshaping and asymmetric distortion).
+#[derive(Copy, Clone, Debug)]
+pub struct DcBlock {
+    coeff: f64,
+    input: f64,
+    output: f64,
+}
+
+impl DcBlock {
+    /// Create a new DC blocker.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        Self {
+            coeff: 1.0 - TAU * CUTOFF / sample_rate.into(),
+            input: 0.0,
+            output: 0.0,
+        }
+    }
+
+    /// Filter the next sample of a signal.
+    #[inline(always)]
+    pub fn filter(&mut self, input: Signal) -> Signal {
+        let x = f64::from(input);
+        self.output = x - self.input + self.coeff * self.output;
+        self.input = x;
+        self.output.into()
+    }
+}
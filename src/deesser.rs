@@ -0,0 +1,61 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{compressor::Compressor, sig::Signal, svf::Svf};
+
+/// Attack time of the band compressor in seconds.
+const ATTACK: f64 = 0.001;
+/// Release time of the band compressor in seconds.
+const RELEASE: f64 = 0.05;
+
+/// De-esser (dynamic EQ).  Compresses only one frequency band of a signal,
+/// for taming sibilance and harsh resonances.
+#[derive(Copy, Clone, Debug)]
+pub struct DeEsser {
+    svf: Svf,
+    compressor: Compressor,
+}
+
+impl DeEsser {
+    /// Create a new de-esser.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        let sample_rate = sample_rate.into();
+        Self {
+            svf: Svf::new(sample_rate),
+            compressor: Compressor::new(sample_rate, ATTACK, RELEASE),
+        }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `freq`: Center frequency in Hz of the band (for example, 6000.0)
+    /// - `q`: The quality factor of the band (higher is narrower)
+    /// - `threshold`: Level in dB of the band above which it is reduced
+    /// - `ratio`: Input to output level ratio of the band above the threshold
+    ///   - range: 1+
+    #[inline(always)]
+    pub fn process<C: Into<Signal>>(
+        &mut self,
+        input: Signal,
+        freq: C,
+        q: f64,
+        threshold: f64,
+        ratio: f64,
+    ) -> Signal {
+        // Normalize bandpass to unity gain at the center frequency, so that
+        // the threshold is the level of the band, and subtracting it cuts
+        // the band by exactly the compressor gain.
+        let band =
+            f64::from(self.svf.filter(input, freq, q).bandpass) / q.max(0.01);
+        let gain = self.compressor.gain(band.into(), threshold, ratio, 0.0);
+        (f64::from(input) - band * (1.0 - gain)).into()
+    }
+}
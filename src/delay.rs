@@ -0,0 +1,65 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{line::Line, sig::Signal};
+
+/// Feedback delay (echo) effect.
+#[derive(Clone, Debug)]
+pub struct Delay {
+    line: Line,
+    sample_rate: f64,
+}
+
+impl Delay {
+    /// Create a new delay effect.
+    ///
+    /// - `max_time`: The maximum delay time in seconds
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R, max_time: f64) -> Self {
+        let sample_rate = sample_rate.into();
+        Self {
+            line: Line::new((max_time * sample_rate).ceil() as usize + 1),
+            sample_rate,
+        }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `delay`: The delay time in samples (may be fractional)
+    /// - `feedback`: Gain of each repeat - range: 0~1 (exclusive)
+    /// - `mix`: Wet/dry mix - range: 0~1 (0.0 for dry only, 1.0 for wet only)
+    #[inline(always)]
+    pub fn process(
+        &mut self,
+        input: Signal,
+        delay: f64,
+        feedback: f64,
+        mix: f64,
+    ) -> Signal {
+        let x = f64::from(input);
+        let wet = self.line.read(delay);
+        self.line.push(x + wet * feedback);
+        (x * (1.0 - mix) + wet * mix).into()
+    }
+
+    /// Process the next sample of a signal, with the delay time in
+    /// milliseconds.
+    #[inline(always)]
+    pub fn process_ms(
+        &mut self,
+        input: Signal,
+        time: f64,
+        feedback: f64,
+        mix: f64,
+    ) -> Signal {
+        let delay = time * 0.001 * self.sample_rate;
+        self.process(input, delay, feedback, mix)
+    }
+}
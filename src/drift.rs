@@ -0,0 +1,89 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{sig::Signal, white::White};
+use std::f64::consts::TAU;
+
+/// Analog oscillator drift and jitter emulation.  A sawtooth phase source
+/// (like [`Fc::freq()`](crate::Fc::freq)) with slow random pitch drift and
+/// random per-cycle pitch jitter, emulating the instability of an analog VCO.
+#[derive(Clone, Debug)]
+pub struct Drift {
+    white: White,
+    sample_rate: f64,
+    // Drift depth and rate
+    depth: f64,
+    rate: f64,
+    // Jitter depth
+    jitter: f64,
+    // Current drift, drift target and time until next target
+    drift: f64,
+    target: f64,
+    timer: f64,
+    // Current jitter (cents)
+    offset: f64,
+    phase: f64,
+}
+
+impl Drift {
+    /// Create a new drifting oscillator phase source.
+    ///
+    /// - `depth`: Maximum slow pitch drift in cents
+    /// - `rate`: How often the drift changes direction in Hz
+    /// - `jitter`: Maximum per-cycle pitch jitter in cents
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        depth: f64,
+        rate: f64,
+        jitter: f64,
+    ) -> Self {
+        Self {
+            white: White::new(),
+            sample_rate: sample_rate.into(),
+            depth,
+            rate,
+            jitter,
+            drift: 0.0,
+            target: 0.0,
+            timer: 0.0,
+            offset: 0.0,
+            phase: 0.0,
+        }
+    }
+
+    /// Change the seed of the random drift and jitter.  Every `Drift`
+    /// starts with the same seed, so give each voice of a
+    /// [`Poly`](crate::Poly) its own seed for independent drift.
+    #[inline(always)]
+    pub fn seed(&mut self, seed: u64) {
+        self.white = White::with_seed(seed);
+    }
+
+    /// Get the next sample of the sawtooth phase with a frequency.
+    #[inline(always)]
+    pub fn freq(&mut self, freq: f64) -> Signal {
+        // Pick a new drift target at the drift rate, and glide towards it.
+        self.timer -= self.rate / self.sample_rate;
+        if self.timer <= 0.0 {
+            self.timer += 1.0;
+            self.target = self.white.noise().into();
+        }
+        let coeff = 1.0 - (-TAU * self.rate / self.sample_rate).exp();
+        self.drift += (self.target - self.drift) * coeff;
+        // Advance the phase, with new jitter on every cycle.
+        let cents = self.drift * self.depth + self.offset;
+        self.phase += freq * 2.0f64.powf(cents / 1200.0) / self.sample_rate;
+        if self.phase >= 1.0 {
+            self.phase = self.phase.fract();
+            self.offset = f64::from(self.white.noise()) * self.jitter;
+        }
+        (self.phase * 2.0 - 1.0).into()
+    }
+}
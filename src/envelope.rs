@@ -0,0 +1,139 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{curve::curve, retrigger::Retrigger, sig::Signal};
+
+/// Multi-segment breakpoint envelope.  Moves from one point to the next,
+/// starting from 0, with an optional loop section that repeats while the note
+/// is on.
+#[derive(Clone, Debug)]
+pub struct Envelope {
+    sample_rate: f64,
+    // Time in seconds since the previous point, level and curvature
+    points: Vec<(f64, f64, f64)>,
+    // First and last point of the loop section
+    looping: Option<(usize, usize)>,
+    retrigger: Retrigger,
+    // Index of the point being moved toward (`None` when not playing)
+    segment: Option<usize>,
+    held: bool,
+    // Progress through the current segment (0 to 1)
+    t: f64,
+    // Level at the start of the current segment
+    start: f64,
+    level: f64,
+}
+
+impl Envelope {
+    /// Create a new envelope with no points.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        Self {
+            sample_rate: sample_rate.into(),
+            points: Vec::new(),
+            looping: None,
+            retrigger: Retrigger::default(),
+            segment: None,
+            held: false,
+            t: 0.0,
+            start: 0.0,
+            level: 0.0,
+        }
+    }
+
+    /// Add a point to the end of the envelope, returning the index of the
+    /// point.
+    ///
+    /// - `time`: Time in seconds to reach this point from the previous one
+    /// - `level`: Level of the point
+    /// - `curvature`: Shape of the segment leading to the point (0.0 for
+    ///   linear, positive to start slow and end fast, negative to start fast
+    ///   and end slow)
+    pub fn point(&mut self, time: f64, level: f64, curvature: f64) -> usize {
+        self.points.push((time, level, curvature));
+        self.points.len() - 1
+    }
+
+    /// Loop between point number `start` and point number `end` while the
+    /// note is on (`None` to not loop).
+    pub fn looping(&mut self, section: Option<(usize, usize)>) {
+        if let Some((start, end)) = section {
+            assert!(start < end && end < self.points.len(), "Invalid loop");
+        }
+        self.looping = section;
+    }
+
+    /// Change what happens when a note starts while the envelope is playing.
+    pub fn retrigger(&mut self, retrigger: Retrigger) {
+        self.retrigger = retrigger;
+    }
+
+    /// Start the envelope from the beginning (by default from the current
+    /// level, so it doesn't click).
+    pub fn note_on(&mut self) {
+        match self.retrigger {
+            Retrigger::Always => self.level = 0.0,
+            Retrigger::FromCurrent => {}
+            Retrigger::Legato => {
+                if self.held && self.segment.is_some() {
+                    return;
+                }
+            }
+        }
+        self.held = true;
+        self.enter(0);
+    }
+
+    /// Let the envelope continue past the loop section to the end.
+    pub fn note_off(&mut self) {
+        self.held = false;
+    }
+
+    /// Returns true once the last point has been reached (or before the first
+    /// note).
+    #[inline(always)]
+    pub fn is_idle(&self) -> bool {
+        self.segment.is_none()
+    }
+
+    /// Generate the next sample of the envelope.
+    #[inline(always)]
+    pub fn gen(&mut self) -> Signal {
+        let index = match self.segment {
+            Some(index) => index,
+            None => return self.level.into(),
+        };
+        let (time, end, curvature) = self.points[index];
+        self.t += (time * self.sample_rate).max(1.0).recip();
+        if self.t >= 1.0 {
+            self.level = end;
+            match self.looping {
+                Some((start, last)) if self.held && index == last => {
+                    self.enter(start + 1)
+                }
+                _ => self.enter(index + 1),
+            }
+        } else {
+            self.level =
+                self.start + (end - self.start) * curve(self.t, curvature);
+        }
+        self.level.into()
+    }
+
+    fn enter(&mut self, index: usize) {
+        self.segment = if index < self.points.len() {
+            Some(index)
+        } else {
+            None
+        };
+        self.start = self.level;
+        self.t = 0.0;
+    }
+}
@@ -0,0 +1,63 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    biquad::{Biquad, BiquadMode},
+    sig::Signal,
+};
+
+/// Multi-band parametric equalizer.  A chain of [`Biquad`] filters, one for
+/// each band.
+#[derive(Clone, Debug)]
+pub struct Equalizer {
+    sample_rate: f64,
+    // Filter, frequency and Q for each band
+    bands: Vec<(Biquad, f64, f64)>,
+}
+
+impl Equalizer {
+    /// Create a new equalizer with no bands.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        Self {
+            sample_rate: sample_rate.into(),
+            bands: Vec::new(),
+        }
+    }
+
+    /// Add a band to the equalizer, returning the index of the band.
+    ///
+    /// - `mode`: The type of band (gain in dB is set with the mode)
+    /// - `freq`: The center (or cutoff) frequency in Hz
+    /// - `q`: The quality factor (bandwidth) of the band
+    pub fn band(&mut self, mode: BiquadMode, freq: f64, q: f64) -> usize {
+        self.bands
+            .push((Biquad::new(self.sample_rate, mode), freq, q));
+        self.bands.len() - 1
+    }
+
+    /// Change the settings of band number `band`.
+    pub fn set(&mut self, band: usize, mode: BiquadMode, freq: f64, q: f64) {
+        let band = &mut self.bands[band];
+        band.0.mode(mode);
+        band.1 = freq;
+        band.2 = q;
+    }
+
+    /// Filter the next sample of a signal through each band.
+    #[inline(always)]
+    pub fn filter(&mut self, input: Signal) -> Signal {
+        self.bands
+            .iter_mut()
+            .fold(input, |signal, (biquad, freq, q)| {
+                biquad.filter(signal, *freq, *q)
+            })
+    }
+}
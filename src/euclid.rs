@@ -0,0 +1,149 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    sig::Signal,
+    tempo::{Division, Transport},
+};
+
+/// Euclidean rhythm generator.  Spreads `hits` as evenly as possible over a
+/// looping pattern of `steps`, and outputs a trigger signal (1 for the first
+/// half of each step with a hit, otherwise 0) for clocking envelopes,
+/// [`StepMod`](crate::StepMod) and [`SampleHold`](crate::SampleHold).
+///
+/// ```rust
+/// use twang::Euclid;
+///
+/// // Cuban tresillo
+/// let euclid = Euclid::new(48_000, 3, 8, 0);
+/// let pattern = [true, false, false, true, false, false, true, false];
+/// assert_eq!(euclid.pattern(), &pattern);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Euclid {
+    sample_rate: f64,
+    pattern: Vec<bool>,
+    // Index of the current step (`None` before the first step)
+    current: Option<usize>,
+    // Progress through the current step (0 to 1)
+    phase: f64,
+    // Transport step of the current step (when synced)
+    tick: Option<u64>,
+    // If a hit started on the last sample
+    hit: bool,
+}
+
+impl Euclid {
+    /// Create a new Euclidean rhythm.
+    ///
+    /// - `hits`: Number of hits (no more than `steps`)
+    /// - `steps`: Number of steps in the pattern (at least 1)
+    /// - `rotation`: Number of steps to rotate the pattern to the right
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        hits: usize,
+        steps: usize,
+        rotation: usize,
+    ) -> Self {
+        let mut euclid = Self {
+            sample_rate: sample_rate.into(),
+            pattern: Vec::new(),
+            current: None,
+            phase: 0.0,
+            tick: None,
+            hit: false,
+        };
+        euclid.set(hits, steps, rotation);
+        euclid
+    }
+
+    /// Change the rhythm (see [`new()`](Euclid::new)).  The current step
+    /// carries on from where it is.
+    pub fn set(&mut self, hits: usize, steps: usize, rotation: usize) {
+        let steps = steps.max(1);
+        let hits = hits.min(steps);
+        self.pattern = (0..steps)
+            .map(|i| (i + steps - rotation % steps) * hits % steps < hits)
+            .collect();
+        self.current = self.current.map(|step| step % steps);
+    }
+
+    /// Get the pattern (true for steps with a hit).
+    #[inline(always)]
+    pub fn pattern(&self) -> &[bool] {
+        &self.pattern
+    }
+
+    /// Go back to before the first step.
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.current = None;
+        self.phase = 0.0;
+        self.tick = None;
+    }
+
+    /// Returns true if a hit started on the last sample generated (for
+    /// playing notes on hits).
+    #[inline(always)]
+    pub fn hit(&self) -> bool {
+        self.hit
+    }
+
+    /// Generate the next sample of the trigger signal, advancing `rate`
+    /// steps per second.
+    #[inline(always)]
+    pub fn gen(&mut self, rate: f64) -> Signal {
+        self.hit = false;
+        if self.current.is_none() || self.phase >= 1.0 {
+            self.phase = self.phase.fract();
+            let len = self.pattern.len();
+            self.advance(self.current.map_or(0, |step| (step + 1) % len));
+        }
+        let out = self.trigger();
+        self.phase += rate / self.sample_rate;
+        out
+    }
+
+    /// Generate the next sample of the trigger signal, with one step per
+    /// `division` of the `transport` (locked to the play position).  Outputs
+    /// 0 while the transport is stopped.
+    #[inline(always)]
+    pub fn sync(
+        &mut self,
+        transport: &Transport,
+        division: Division,
+    ) -> Signal {
+        self.hit = false;
+        if !transport.is_playing() {
+            return Signal::from(0.0);
+        }
+        let tick = transport.step(division);
+        if self.tick != Some(tick) {
+            self.tick = Some(tick);
+            self.advance((tick % self.pattern.len() as u64) as usize);
+        }
+        self.phase = transport.phase(division);
+        self.trigger()
+    }
+
+    /// Move to step number `next`.
+    #[inline(always)]
+    fn advance(&mut self, next: usize) {
+        self.hit = self.pattern[next];
+        self.current = Some(next);
+    }
+
+    /// Get the trigger signal for the current step.
+    #[inline(always)]
+    fn trigger(&self) -> Signal {
+        let hit = self.current.map_or(false, |step| self.pattern[step]);
+        Signal::from(if hit && self.phase < 0.5 { 1.0 } else { 0.0 })
+    }
+}
@@ -0,0 +1,57 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{sig::Signal, svf::Svf};
+use std::f64::consts::FRAC_1_SQRT_2;
+
+/// Harmonic exciter.  Adds sheen by saturating the high frequencies of a
+/// signal, and blending the newly generated harmonics back in.
+#[derive(Copy, Clone, Debug)]
+pub struct Exciter {
+    // Highpass before saturation
+    band: Svf,
+    // Highpass after saturation, removing low intermodulation products
+    harmonics: Svf,
+}
+
+impl Exciter {
+    /// Create a new harmonic exciter.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        let sample_rate = sample_rate.into();
+        Self {
+            band: Svf::new(sample_rate),
+            harmonics: Svf::new(sample_rate),
+        }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `freq`: Frequency in Hz above which harmonics are generated
+    /// - `drive`: Saturation gain (more for denser harmonics) - range: 1+
+    /// - `amount`: Gain of the harmonics added to the input - range: 0~1
+    #[inline(always)]
+    pub fn process<C: Into<Signal>>(
+        &mut self,
+        input: Signal,
+        freq: C,
+        drive: f64,
+        amount: f64,
+    ) -> Signal {
+        let freq = freq.into();
+        let band = self.band.filter(input, freq, FRAC_1_SQRT_2).highpass;
+        let saturated = (f64::from(band) * drive).tanh();
+        let harmonics = self
+            .harmonics
+            .filter(saturated.into(), freq, FRAC_1_SQRT_2)
+            .highpass;
+        (f64::from(input) + f64::from(harmonics) * amount).into()
+    }
+}
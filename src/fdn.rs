@@ -0,0 +1,161 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{line::Line, sig::Signal};
+use std::f64::consts::TAU;
+
+/// Shortest delay line length in seconds.
+const MIN_TIME: f64 = 0.021;
+/// Longest delay line length in seconds.
+const MAX_TIME: f64 = 0.067;
+
+/// Mixing matrix used to feed each delay line of an [`Fdn`] back into all of
+/// the others.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FdnMatrix {
+    /// Householder reflection (cheapest, less dense echoes).
+    Householder,
+    /// Normalized Hadamard matrix (densest echoes).
+    Hadamard,
+}
+
+/// Per-line state of an [`Fdn`].
+#[derive(Clone, Debug)]
+struct Channel {
+    line: Line,
+    delay: f64,
+    // Gain below and above the crossover frequency
+    low: f64,
+    high: f64,
+    // Crossover lowpass state
+    state: f64,
+}
+
+/// Feedback delay network reverb.  Delay lines of mutually different lengths
+/// are mixed into each other through a lossless matrix, with a decay that
+/// varies between low and high frequencies.
+#[derive(Clone, Debug)]
+pub struct Fdn {
+    channels: Vec<Channel>,
+    matrix: FdnMatrix,
+    sample_rate: f64,
+    // Crossover lowpass coefficient
+    coeff: f64,
+    // Output scratch space for the mixing matrix
+    mixed: Vec<f64>,
+}
+
+impl Fdn {
+    /// Create a new feedback delay network reverb, with an RT60 of 2 seconds
+    /// below 4 KHz, and 1 second above.
+    ///
+    /// - `lines`: Number of delay lines - 4, 8 or 16
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        lines: usize,
+        matrix: FdnMatrix,
+    ) -> Self {
+        assert!(
+            matches!(lines, 4 | 8 | 16),
+            "Fdn needs 4, 8 or 16 delay lines"
+        );
+        let sample_rate = sample_rate.into();
+        let channels = (0..lines)
+            .map(|i| {
+                let ratio = MAX_TIME / MIN_TIME;
+                let time = MIN_TIME * ratio.powf(i as f64 / (lines - 1) as f64);
+                // Odd lengths avoid most common factors between lines
+                let delay = ((time * sample_rate) as usize | 1) as f64;
+                Channel {
+                    line: Line::new(delay as usize),
+                    delay,
+                    low: 0.0,
+                    high: 0.0,
+                    state: 0.0,
+                }
+            })
+            .collect();
+        let mut fdn = Self {
+            channels,
+            matrix,
+            sample_rate,
+            coeff: 0.0,
+            mixed: vec![0.0; lines],
+        };
+        fdn.decay(2.0, 1.0, 4_000.0);
+        fdn
+    }
+
+    /// Change the decay time.
+    ///
+    /// - `rt60`: Time in seconds for low frequencies to decay by 60 dB
+    /// - `rt60_high`: Time in seconds for high frequencies to decay by 60 dB
+    /// - `crossover`: Frequency in Hz between low and high frequencies
+    pub fn decay(&mut self, rt60: f64, rt60_high: f64, crossover: f64) {
+        let sample_rate = self.sample_rate;
+        let gain = |delay: f64, rt60: f64| {
+            10.0f64.powf(-3.0 * delay / (rt60.max(f64::EPSILON) * sample_rate))
+        };
+        for channel in self.channels.iter_mut() {
+            channel.low = gain(channel.delay, rt60);
+            channel.high = gain(channel.delay, rt60_high);
+        }
+        self.coeff = (-TAU * crossover / sample_rate).exp();
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `mix`: Wet/dry mix - range: 0~1 (0.0 for dry only, 1.0 for wet only)
+    #[inline(always)]
+    pub fn process(&mut self, input: Signal, mix: f64) -> Signal {
+        let x = f64::from(input);
+        let mut wet = 0.0;
+        for (channel, mixed) in self.channels.iter_mut().zip(&mut self.mixed) {
+            let out = channel.line.read(channel.delay);
+            channel.state = out + self.coeff * (channel.state - out);
+            *mixed = channel.high * out
+                + (channel.low - channel.high) * channel.state;
+            wet += out;
+        }
+        let n = self.mixed.len();
+        match self.matrix {
+            FdnMatrix::Householder => {
+                let sum = self.mixed.iter().sum::<f64>() * 2.0 / n as f64;
+                for mixed in self.mixed.iter_mut() {
+                    *mixed -= sum;
+                }
+            }
+            FdnMatrix::Hadamard => {
+                // Fast Walsh-Hadamard transform
+                let mut half = 1;
+                while half < n {
+                    for i in (0..n).step_by(half * 2) {
+                        for j in i..i + half {
+                            let a = self.mixed[j];
+                            let b = self.mixed[j + half];
+                            self.mixed[j] = a + b;
+                            self.mixed[j + half] = a - b;
+                        }
+                    }
+                    half *= 2;
+                }
+                let scale = (n as f64).sqrt().recip();
+                for mixed in self.mixed.iter_mut() {
+                    *mixed *= scale;
+                }
+            }
+        }
+        for (channel, mixed) in self.channels.iter_mut().zip(&self.mixed) {
+            channel.line.push(x + mixed);
+        }
+        wet /= n as f64;
+        (x * (1.0 - mix) + wet * mix).into()
+    }
+}
@@ -0,0 +1,151 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use std::f64::consts::TAU;
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+/// Complex number.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(crate) struct Complex {
+    pub(crate) re: f64,
+    pub(crate) im: f64,
+}
+
+impl Complex {
+    #[inline(always)]
+    pub(crate) fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    #[inline(always)]
+    pub(crate) fn polar(norm: f64, arg: f64) -> Self {
+        Self::new(norm * arg.cos(), norm * arg.sin())
+    }
+
+    #[inline(always)]
+    pub(crate) fn norm(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    #[inline(always)]
+    pub(crate) fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl AddAssign for Complex {
+    #[inline(always)]
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    #[inline(always)]
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+/// Radix-2 fast fourier transform of a fixed power of two size.
+#[derive(Clone, Debug)]
+pub(crate) struct Fft {
+    twiddles: Vec<Complex>,
+}
+
+impl Fft {
+    pub(crate) fn new(size: usize) -> Self {
+        assert!(size.is_power_of_two(), "FFT size must be a power of two");
+        let twiddles = (0..size / 2)
+            .map(|i| Complex::polar(1.0, -TAU * i as f64 / size as f64))
+            .collect();
+        Self { twiddles }
+    }
+
+    /// Transform size.
+    #[inline(always)]
+    pub(crate) fn size(&self) -> usize {
+        self.twiddles.len() * 2
+    }
+
+    /// In-place forward transform.
+    pub(crate) fn forward(&self, data: &mut [Complex]) {
+        self.transform(data, false);
+    }
+
+    /// In-place inverse transform (scaled, so that it undoes `forward()`).
+    pub(crate) fn inverse(&self, data: &mut [Complex]) {
+        self.transform(data, true);
+        let scale = (data.len() as f64).recip();
+        for x in data.iter_mut() {
+            x.re *= scale;
+            x.im *= scale;
+        }
+    }
+
+    fn transform(&self, data: &mut [Complex], inverse: bool) {
+        let n = self.size();
+        assert_eq!(data.len(), n);
+        // Bit reversal permutation
+        let mut j = 0;
+        for i in 1..n {
+            let mut bit = n >> 1;
+            while j & bit != 0 {
+                j ^= bit;
+                bit >>= 1;
+            }
+            j |= bit;
+            if i < j {
+                data.swap(i, j);
+            }
+        }
+        // Butterflies
+        let mut len = 2;
+        while len <= n {
+            let step = n / len;
+            for start in (0..n).step_by(len) {
+                for k in 0..len / 2 {
+                    let mut w = self.twiddles[k * step];
+                    if inverse {
+                        w.im = -w.im;
+                    }
+                    let a = data[start + k];
+                    let b = data[start + k + len / 2] * w;
+                    data[start + k] = a + b;
+                    data[start + k + len / 2] = a - b;
+                }
+            }
+            len <<= 1;
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{line::Line, sig::Signal};
+use std::f64::consts::TAU;
+
+/// Maximum delay time sweep in seconds.
+const SWEEP: f64 = 0.005;
+
+/// Flanger effect.  A short delay with feedback, with the delay time modulated
+/// by an LFO.
+#[derive(Clone, Debug)]
+pub struct Flanger {
+    line: Line,
+    dry: Line,
+    sample_rate: f64,
+    through_zero: bool,
+    // LFO phase (0 to 1)
+    phase: f64,
+}
+
+impl Flanger {
+    /// Create a new flanger effect.
+    ///
+    /// - `through_zero`: If true, the dry signal is delayed by half of the
+    ///   sweep so that the modulated delay can pass through zero relative to it
+    pub fn new<R: Into<f64>>(sample_rate: R, through_zero: bool) -> Self {
+        let sample_rate = sample_rate.into();
+        let max = (2.0 * SWEEP * sample_rate).ceil() as usize + 2;
+        Self {
+            line: Line::new(max),
+            dry: Line::new(max),
+            sample_rate,
+            through_zero,
+            phase: 0.0,
+        }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `rate`: LFO frequency in Hz
+    /// - `depth`: Amount of delay time modulation - range: 0~1
+    /// - `feedback`: Gain of the delayed signal fed back into the delay -
+    ///   range: -1~1 (exclusive)
+    /// - `mix`: Wet/dry mix - range: 0~1 (0.5 for the deepest notches)
+    #[inline(always)]
+    pub fn process(
+        &mut self,
+        input: Signal,
+        rate: f64,
+        depth: f64,
+        feedback: f64,
+        mix: f64,
+    ) -> Signal {
+        let x = f64::from(input);
+        self.phase = (self.phase + rate / self.sample_rate).fract();
+        let lfo = (TAU * self.phase).sin();
+        let depth = depth.clamp(0.0, 1.0);
+        let (delay, dry) = if self.through_zero {
+            let center = SWEEP * self.sample_rate;
+            let dry = self.dry.read(center);
+            self.dry.push(x);
+            (center * (1.0 + depth * lfo), dry)
+        } else {
+            (SWEEP * self.sample_rate * depth * (1.0 + lfo), x)
+        };
+        let wet = self.line.read(delay);
+        self.line.push(x + wet * feedback);
+        (dry * (1.0 - mix) + wet * mix).into()
+    }
+}
@@ -0,0 +1,103 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{sig::Signal, synth::Fc};
+
+/// A sine wave FM operator.
+#[derive(Copy, Clone, Debug)]
+pub struct Operator {
+    ratio: f64,
+    level: f64,
+    feedback: f64,
+}
+
+impl Operator {
+    /// Create a new FM operator.
+    ///
+    /// - `ratio`: Frequency ratio to the note frequency
+    /// - `level`: Output level (modulation index when used as a modulator)
+    /// - `feedback`: Amount the operator modulates itself
+    #[inline(always)]
+    pub fn new(ratio: f64, level: f64, feedback: f64) -> Self {
+        Self {
+            ratio,
+            level,
+            feedback,
+        }
+    }
+}
+
+/// FM (Phase Modulation) synthesis with a stack of operators wired together
+/// in an algorithm (as in DX7-style synthesizers).
+///
+/// Operators may only be modulated by operators with a higher index, so
+/// operator 0 is usually a carrier.
+#[derive(Clone, Debug)]
+pub struct Fm {
+    ops: Vec<Operator>,
+    // List of modulators for each operator
+    mods: Vec<Vec<usize>>,
+    carriers: Vec<usize>,
+    // Last two outputs of each operator, for feedback
+    prev: Vec<[f64; 2]>,
+    out: Vec<f64>,
+}
+
+impl Fm {
+    /// Create a new FM synthesizer with a list of operators, and no
+    /// connections between them.
+    pub fn new(ops: Vec<Operator>) -> Self {
+        let len = ops.len();
+        Self {
+            ops,
+            mods: vec![Vec::new(); len],
+            carriers: Vec::new(),
+            prev: vec![[0.0; 2]; len],
+            out: vec![0.0; len],
+        }
+    }
+
+    /// Route the output of operator `modulator` into the phase of operator
+    /// `op`.
+    pub fn modulate(&mut self, modulator: usize, op: usize) {
+        assert!(modulator > op, "Fm: modulator must have a higher index");
+        self.mods[op].push(modulator);
+    }
+
+    /// Route the output of operator `op` to the output of the synthesizer.
+    pub fn carrier(&mut self, op: usize) {
+        self.carriers.push(op);
+    }
+
+    /// Generate the next sample.
+    ///
+    /// - `fc`: The frequency counter
+    /// - `freq`: The note frequency
+    /// - `env`: Envelope level for each operator (`1.0` for no envelope)
+    pub fn gen(&mut self, fc: &Fc, freq: f64, env: &[Signal]) -> Signal {
+        for i in (0..self.ops.len()).rev() {
+            let op = self.ops[i];
+            let [a, b] = self.prev[i];
+            let phase = self.mods[i].iter().map(|m| self.out[*m]).sum::<f64>()
+                + (a + b) * 0.5 * op.feedback;
+            let level = env.get(i).map(|e| f64::from(*e)).unwrap_or(1.0);
+            let out = f64::from(fc.freq(freq * op.ratio).shift(phase).sine())
+                * op.level
+                * level;
+            self.prev[i] = [out, a];
+            self.out[i] = out;
+        }
+        self.carriers
+            .iter()
+            .map(|c| self.out[*c])
+            .sum::<f64>()
+            .into()
+    }
+}
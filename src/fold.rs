@@ -0,0 +1,19 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+/// Shape of the folds of a wavefolder (see
+/// [`Signal::fold()`](crate::Signal::fold)).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Fold {
+    /// Smooth folds (sine transfer function).
+    Sine,
+    /// Sharp folds (triangle transfer function), reflecting at -1 and 1.
+    Triangle,
+}
@@ -0,0 +1,71 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+
+/// Envelope follower.  Converts an audio signal into a control signal that
+/// follows it's level (rectified and smoothed).
+#[derive(Copy, Clone, Debug)]
+pub struct Follower {
+    sample_rate: f64,
+    attack: f64,
+    release: f64,
+    envelope: f64,
+}
+
+impl Follower {
+    /// Create a new envelope follower.
+    ///
+    /// - `attack`: Time in seconds to follow a rising level
+    /// - `release`: Time in seconds to follow a falling level
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        attack: f64,
+        release: f64,
+    ) -> Self {
+        let mut follower = Self {
+            sample_rate: sample_rate.into(),
+            attack: 0.0,
+            release: 0.0,
+            envelope: 0.0,
+        };
+        follower.time(attack, release);
+        follower
+    }
+
+    /// Change the attack and release times in seconds.
+    #[inline(always)]
+    pub fn time(&mut self, attack: f64, release: f64) {
+        let sample_rate = self.sample_rate;
+        let coeff = |time: f64| {
+            if time > 0.0 {
+                (-1.0 / (time * sample_rate)).exp()
+            } else {
+                0.0
+            }
+        };
+        self.attack = coeff(attack);
+        self.release = coeff(release);
+    }
+
+    /// Follow the next sample of a signal, returning the level (0 or more).
+    #[inline(always)]
+    pub fn follow(&mut self, input: Signal) -> Signal {
+        let level = f64::from(input).abs();
+        let coeff = if level > self.envelope {
+            self.attack
+        } else {
+            self.release
+        };
+        self.envelope = level + coeff * (self.envelope - level);
+        self.envelope.into()
+    }
+}
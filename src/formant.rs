@@ -0,0 +1,52 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{sig::Signal, svf::Svf, vowel::formants};
+
+/// Formant filter bank.  Parallel bandpass resonators tuned to vowel formants,
+/// for making any source (sawtooth, noise) "talk".
+#[derive(Copy, Clone, Debug)]
+pub struct Formant {
+    filters: [Svf; 3],
+}
+
+impl Formant {
+    /// Create a new formant filter bank.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        let sample_rate = sample_rate.into();
+        Self {
+            filters: [Svf::new(sample_rate); 3],
+        }
+    }
+
+    /// Filter the next sample of a signal.
+    ///
+    /// - `vowel`: Morph between vowels - range: 0~1 (0.0 for A, 0.25 for E,
+    ///   0.5 for I, 0.75 for O, and 1.0 for U)
+    #[inline(always)]
+    pub fn filter<S: Into<Signal>>(
+        &mut self,
+        input: Signal,
+        vowel: S,
+    ) -> Signal {
+        let formants = formants(vowel.into().into());
+        self.filters
+            .iter_mut()
+            .zip(formants.iter())
+            .map(|(filter, (freq, gain, bw))| {
+                let q = freq / bw;
+                // Normalize bandpass to unity gain at the center frequency.
+                f64::from(filter.filter(input, *freq, q).bandpass) * gain / q
+            })
+            .sum::<f64>()
+            .into()
+    }
+}
@@ -0,0 +1,101 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{allpass::Allpass, line::Line, sig::Signal};
+
+/// Comb delay times in samples at 44.1 KHz.
+const COMBS: [f64; 8] = [
+    1116.0, 1188.0, 1277.0, 1356.0, 1422.0, 1491.0, 1557.0, 1617.0,
+];
+/// Allpass delay times in samples at 44.1 KHz.
+const ALLPASSES: [f64; 4] = [556.0, 441.0, 341.0, 225.0];
+/// Gain applied to the input before reverberation.
+const INPUT_GAIN: f64 = 0.015;
+/// Gain applied to the reverberated output.
+const WET_GAIN: f64 = 3.0;
+
+/// Comb filter with a one-pole lowpass in it's feedback path.
+#[derive(Clone, Debug)]
+struct DampedComb {
+    line: Line,
+    delay: f64,
+    store: f64,
+}
+
+impl DampedComb {
+    #[inline(always)]
+    fn filter(&mut self, input: f64, feedback: f64, damping: f64) -> f64 {
+        let output = self.line.read(self.delay);
+        self.store = output * (1.0 - damping) + self.store * damping;
+        self.line.push(input + self.store * feedback);
+        output
+    }
+}
+
+/// Freeverb-style algorithmic reverb.  Eight damped comb filters in parallel,
+/// followed by four allpass filters in series.
+#[derive(Clone, Debug)]
+pub struct Freeverb {
+    combs: Vec<DampedComb>,
+    allpasses: Vec<(Allpass, f64)>,
+}
+
+impl Freeverb {
+    /// Create a new freeverb reverb effect.
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        let scale = sample_rate.into() / 44_100.0;
+        let combs = COMBS
+            .iter()
+            .map(|delay| {
+                let delay = (delay * scale).round().max(1.0);
+                DampedComb {
+                    line: Line::new(delay as usize),
+                    delay,
+                    store: 0.0,
+                }
+            })
+            .collect();
+        let allpasses = ALLPASSES
+            .iter()
+            .map(|delay| {
+                let delay = (delay * scale).round().max(1.0);
+                (Allpass::new(delay as usize), delay)
+            })
+            .collect();
+        Self { combs, allpasses }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `room_size`: Length of the reverb tail - range: 0~1
+    /// - `damping`: Amount of high frequency loss in the tail - range: 0~1
+    /// - `mix`: Wet/dry mix - range: 0~1 (0.0 for dry only, 1.0 for wet only)
+    #[inline(always)]
+    pub fn process(
+        &mut self,
+        input: Signal,
+        room_size: f64,
+        damping: f64,
+        mix: f64,
+    ) -> Signal {
+        let x = f64::from(input);
+        let feedback = 0.7 + 0.28 * room_size.clamp(0.0, 1.0);
+        let damping = 0.4 * damping.clamp(0.0, 1.0);
+        let mut wet = 0.0;
+        for comb in self.combs.iter_mut() {
+            wet += comb.filter(x * INPUT_GAIN, feedback, damping);
+        }
+        let mut wet = Signal::from(wet);
+        for (allpass, delay) in self.allpasses.iter_mut() {
+            wet = allpass.filter(wet, *delay, 0.5);
+        }
+        (x * (1.0 - mix) + f64::from(wet) * WET_GAIN * mix).into()
+    }
+}
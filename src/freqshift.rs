@@ -0,0 +1,86 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+use std::f64::consts::TAU;
+
+/// Allpass coefficients of the two paths of the Hilbert transformer (Olli
+/// Niemitalo's design, about 90° apart from 20 Hz to 20 KHz at 44.1 KHz).
+const COEFFS: [[f64; 4]; 2] = [
+    [0.6923878, 0.9360654322959, 0.9882295226860, 0.9987488452737],
+    [
+        0.4021921162426,
+        0.8561710882420,
+        0.9722909545651,
+        0.9952884791278,
+    ],
+];
+
+/// Chain of second order allpass sections in one Hilbert transformer path.
+#[derive(Copy, Clone, Debug, Default)]
+struct Path {
+    // Last two inputs and outputs of each section
+    x: [[f64; 2]; 4],
+    y: [[f64; 2]; 4],
+}
+
+impl Path {
+    #[inline(always)]
+    fn filter(&mut self, input: f64, coeffs: &[f64; 4]) -> f64 {
+        let mut signal = input;
+        for (i, a) in coeffs.iter().enumerate() {
+            let out = a * a * (signal + self.y[i][1]) - self.x[i][1];
+            self.x[i] = [signal, self.x[i][0]];
+            self.y[i] = [out, self.y[i][0]];
+            signal = out;
+        }
+        signal
+    }
+}
+
+/// Frequency shifter (Bode style).  Shifts every frequency of a signal by the
+/// same amount in Hz using a Hilbert transform, which (unlike pitch shifting)
+/// makes harmonic sounds inharmonic.
+#[derive(Copy, Clone, Debug)]
+pub struct FreqShift {
+    paths: [Path; 2],
+    sample_rate: f64,
+    // One sample delay of the first path
+    delayed: f64,
+    // Oscillator phase (0 to 1)
+    phase: f64,
+}
+
+impl FreqShift {
+    /// Create a new frequency shifter.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        Self {
+            paths: [Path::default(); 2],
+            sample_rate: sample_rate.into(),
+            delayed: 0.0,
+            phase: 0.0,
+        }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `shift`: Amount to shift in Hz (negative to shift down)
+    #[inline(always)]
+    pub fn process(&mut self, input: Signal, shift: f64) -> Signal {
+        let x = f64::from(input);
+        let real = self.delayed;
+        self.delayed = self.paths[0].filter(x, &COEFFS[0]);
+        let imag = self.paths[1].filter(x, &COEFFS[1]);
+        self.phase = (self.phase + shift / self.sample_rate).rem_euclid(1.0);
+        let (sin, cos) = (TAU * self.phase).sin_cos();
+        (real * cos + imag * sin).into()
+    }
+}
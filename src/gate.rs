@@ -0,0 +1,130 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    compressor::{from_db, to_db},
+    sig::Signal,
+};
+
+/// Noise gate / expander.  Unlike [`Signal::gate`], the gain is faded in and
+/// out with attack and release envelopes, and held open for a minimum time,
+/// so that it doesn't click.
+#[derive(Copy, Clone, Debug)]
+pub struct Gate {
+    sample_rate: f64,
+    attack: f64,
+    release: f64,
+    // Hold time in samples
+    hold: f64,
+    // Samples left until the gate starts closing
+    countdown: f64,
+    // Linear gain
+    gain: f64,
+}
+
+impl Gate {
+    /// Create a new noise gate.
+    ///
+    /// - `attack`: Time in seconds to open the gate
+    /// - `hold`: Minimum time in seconds to stay open after the level drops
+    /// - `release`: Time in seconds to close the gate
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        attack: f64,
+        hold: f64,
+        release: f64,
+    ) -> Self {
+        let mut gate = Self {
+            sample_rate: sample_rate.into(),
+            attack: 0.0,
+            release: 0.0,
+            hold: 0.0,
+            countdown: 0.0,
+            gain: 0.0,
+        };
+        gate.time(attack, hold, release);
+        gate
+    }
+
+    /// Change the attack, hold and release times in seconds.
+    pub fn time(&mut self, attack: f64, hold: f64, release: f64) {
+        let sample_rate = self.sample_rate;
+        let coeff = |time: f64| {
+            if time > 0.0 {
+                (-1.0 / (time * sample_rate)).exp()
+            } else {
+                0.0
+            }
+        };
+        self.attack = coeff(attack);
+        self.release = coeff(release);
+        self.hold = hold * sample_rate;
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `threshold`: Level in dB above which the gate opens
+    /// - `hysteresis`: How far in dB below `threshold` the level must drop
+    ///   before the gate closes
+    /// - `range`: Gain in dB when the gate is closed (negative, use
+    ///   `f64::NEG_INFINITY` for silence, or a higher value to expand)
+    #[inline(always)]
+    pub fn process(
+        &mut self,
+        input: Signal,
+        threshold: f64,
+        hysteresis: f64,
+        range: f64,
+    ) -> Signal {
+        self.sidechain(input, input, threshold, hysteresis, range)
+    }
+
+    /// Process the next sample of a signal, opening and closing the gate
+    /// based on the level of `key` rather than `input` (for example, to gate
+    /// a reverb tail with the dry sound).  Parameters are the same as
+    /// [`process()`](Self::process).
+    #[inline(always)]
+    pub fn sidechain(
+        &mut self,
+        input: Signal,
+        key: Signal,
+        threshold: f64,
+        hysteresis: f64,
+        range: f64,
+    ) -> Signal {
+        let gain = self.gain(key, threshold, hysteresis, range);
+        input.gain(gain)
+    }
+
+    /// Calculate the next linear gain from the level of `key`.
+    #[inline(always)]
+    pub(crate) fn gain(
+        &mut self,
+        key: Signal,
+        threshold: f64,
+        hysteresis: f64,
+        range: f64,
+    ) -> f64 {
+        let level = to_db(f64::from(key).abs());
+        let open = self.countdown > 0.0;
+        if level >= threshold || (open && level >= threshold - hysteresis) {
+            self.countdown = self.hold.max(1.0);
+        } else {
+            self.countdown -= 1.0;
+        }
+        let (target, coeff) = if self.countdown > 0.0 {
+            (1.0, self.attack)
+        } else {
+            (from_db(range).min(1.0), self.release)
+        };
+        self.gain = target + coeff * (self.gain - target);
+        self.gain
+    }
+}
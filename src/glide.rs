@@ -0,0 +1,82 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{note::Note, smoothed::Smoothed};
+
+/// Monophonic portamento (glide).  Slides the frequency from the previous
+/// note to each new note, linearly in pitch (the same amount of time per
+/// semitone at any pitch).
+#[derive(Copy, Clone, Debug)]
+pub struct Glide {
+    // Note number
+    pitch: Smoothed<f64>,
+    fingered: bool,
+    // Number of notes held down
+    held: usize,
+    started: bool,
+}
+
+impl Glide {
+    /// Create a new glide.
+    ///
+    /// - `time`: Time in seconds to slide to each new note
+    /// - `fingered`: Only slide when the new note starts while another note
+    ///   is still held down (legato playing), otherwise jump to it
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        time: f64,
+        fingered: bool,
+    ) -> Self {
+        Self {
+            pitch: Smoothed::new(sample_rate, time, 69.0),
+            fingered,
+            held: 0,
+            started: false,
+        }
+    }
+
+    /// Change the glide time in seconds (takes effect at the next note).
+    #[inline(always)]
+    pub fn time(&mut self, time: f64) {
+        self.pitch.time(time);
+    }
+
+    /// Slide to a new note (or jump to it when it's the first note, or there
+    /// are no notes held down in fingered mode).
+    #[inline(always)]
+    pub fn note_on(&mut self, note: Note) {
+        if !self.started || (self.fingered && self.held == 0) {
+            self.pitch.reset(note.number());
+        } else {
+            self.pitch.set(note.number());
+        }
+        self.started = true;
+        self.held += 1;
+    }
+
+    /// Let go of a note (only matters in fingered mode).
+    #[inline(always)]
+    pub fn note_off(&mut self, _note: Note) {
+        self.held = self.held.saturating_sub(1);
+    }
+
+    /// Returns true while sliding.
+    #[inline(always)]
+    pub fn is_gliding(&self) -> bool {
+        self.pitch.is_smoothing()
+    }
+
+    /// Generate the next frequency in Hz.
+    #[inline(always)]
+    pub fn gen(&mut self) -> f64 {
+        Note::new(self.pitch.gen()).freq()
+    }
+}
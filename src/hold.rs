@@ -0,0 +1,46 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+
+/// Sample-and-hold.  Latches the value of a signal whenever a trigger signal
+/// crosses a threshold, holding it until the next trigger.
+#[derive(Copy, Clone, Debug)]
+pub struct SampleHold {
+    threshold: f64,
+    trigger: f64,
+    value: Signal,
+}
+
+impl SampleHold {
+    /// Create a new sample-and-hold.
+    ///
+    /// - `threshold`: The level the trigger must rise above to latch
+    #[inline(always)]
+    pub fn new(threshold: f64) -> Self {
+        Self {
+            threshold,
+            trigger: f64::INFINITY,
+            value: 0.0.into(),
+        }
+    }
+
+    /// Get the next sample, latching `input` if `trigger` rises across the
+    /// threshold.
+    #[inline(always)]
+    pub fn hold(&mut self, input: Signal, trigger: Signal) -> Signal {
+        let trigger = f64::from(trigger);
+        if self.trigger <= self.threshold && trigger > self.threshold {
+            self.value = input;
+        }
+        self.trigger = trigger;
+        self.value
+    }
+}
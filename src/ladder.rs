@@ -0,0 +1,62 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+use std::f64::consts::PI;
+
+/// Moog-style 4-pole (24 dB/octave) resonant lowpass ladder filter, with
+/// saturation.
+#[derive(Copy, Clone, Debug)]
+pub struct Ladder {
+    sample_rate: f64,
+    stages: [f64; 4],
+    out: f64,
+}
+
+impl Ladder {
+    /// Create a new ladder filter.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        Self {
+            sample_rate: sample_rate.into(),
+            stages: [0.0; 4],
+            out: 0.0,
+        }
+    }
+
+    /// Filter the next sample of a signal.
+    ///
+    /// - `cutoff`: The cutoff frequency in Hz
+    /// - `resonance`: Resonance - range: 0~1 (self-oscillates at 1.0)
+    /// - `drive`: Input gain into the saturation (1.0 for unity)
+    #[inline(always)]
+    pub fn filter<C: Into<Signal>>(
+        &mut self,
+        input: Signal,
+        cutoff: C,
+        resonance: f64,
+        drive: f64,
+    ) -> Signal {
+        let cutoff =
+            f64::from(cutoff.into()).clamp(0.0, self.sample_rate * 0.49);
+        let g = (PI * cutoff / self.sample_rate).tan();
+        let g = g / (1.0 + g);
+        let k = 4.0 * resonance.max(0.0);
+        let mut x = (f64::from(input) * drive - k * self.out).tanh();
+        for s in self.stages.iter_mut() {
+            // One-pole lowpass (topology-preserving transform)
+            let v = (x - *s) * g;
+            x = v + *s;
+            *s = x + v;
+        }
+        self.out = x;
+        x.into()
+    }
+}
@@ -0,0 +1,112 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{line::Line, sig::Signal, svf::Svf};
+use std::f64::consts::{FRAC_1_SQRT_2, TAU};
+
+/// Crossover frequency between the drum and horn in Hz.
+const CROSSOVER: f64 = 800.0;
+
+/// One rotating speaker of a [`Leslie`].
+#[derive(Clone, Debug)]
+struct Rotor {
+    line: Line,
+    // Slow and fast speeds in Hz
+    speeds: (f64, f64),
+    // Acceleration smoothing coefficient
+    inertia: f64,
+    // Current speed in Hz
+    speed: f64,
+    // Rotation (0 to 1)
+    angle: f64,
+    // Doppler delay depth in samples
+    doppler: f64,
+    // Amplitude modulation depth (0 to 1)
+    tremolo: f64,
+}
+
+impl Rotor {
+    fn new(
+        sample_rate: f64,
+        speeds: (f64, f64),
+        inertia: f64,
+        doppler: f64,
+        tremolo: f64,
+    ) -> Self {
+        let doppler = doppler * sample_rate;
+        Self {
+            line: Line::new((doppler * 2.0).ceil() as usize + 2),
+            speeds,
+            inertia: (-1.0 / (inertia * sample_rate)).exp(),
+            speed: speeds.0,
+            angle: 0.0,
+            doppler,
+            tremolo,
+        }
+    }
+
+    #[inline(always)]
+    fn process(&mut self, x: f64, sample_rate: f64, fast: bool) -> [f64; 2] {
+        let target = if fast { self.speeds.1 } else { self.speeds.0 };
+        self.speed = target + self.inertia * (self.speed - target);
+        self.angle = (self.angle + self.speed / sample_rate).fract();
+        self.line.push(x);
+        // Microphones on opposite sides of the cabinet
+        let mut out = [0.0; 2];
+        for (i, out) in out.iter_mut().enumerate() {
+            let angle = TAU * (self.angle + i as f64 * 0.5);
+            let delay = 1.0 + self.doppler * (1.0 + angle.sin());
+            let gain = 1.0 - self.tremolo * (1.0 - angle.cos()) * 0.5;
+            *out = self.line.read(delay) * gain;
+        }
+        out
+    }
+}
+
+/// Rotary speaker (Leslie) simulation.  Splits the signal between a treble
+/// horn and a bass drum, each spinning at their own speed, producing Doppler
+/// and amplitude modulation picked up by a pair of stereo microphones.
+#[derive(Clone, Debug)]
+pub struct Leslie {
+    sample_rate: f64,
+    crossover: Svf,
+    horn: Rotor,
+    drum: Rotor,
+}
+
+impl Leslie {
+    /// Create a new rotary speaker simulation.
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        let sample_rate = sample_rate.into();
+        Self {
+            sample_rate,
+            crossover: Svf::new(sample_rate),
+            horn: Rotor::new(sample_rate, (0.8, 6.8), 0.6, 0.0004, 0.6),
+            drum: Rotor::new(sample_rate, (0.7, 5.9), 3.5, 0.0002, 0.3),
+        }
+    }
+
+    /// Process the next sample of a signal, returning a stereo (left, right)
+    /// sample.
+    ///
+    /// - `fast`: If true, the rotors accelerate to fast (tremolo) speed,
+    ///   otherwise they slow down to chorale speed
+    #[inline(always)]
+    pub fn process(&mut self, input: Signal, fast: bool) -> [Signal; 2] {
+        let bands = self.crossover.filter(input, CROSSOVER, FRAC_1_SQRT_2);
+        let horn =
+            self.horn
+                .process(bands.highpass.into(), self.sample_rate, fast);
+        let drum =
+            self.drum
+                .process(bands.lowpass.into(), self.sample_rate, fast);
+        [(horn[0] + drum[0]).into(), (horn[1] + drum[1]).into()]
+    }
+}
@@ -0,0 +1,162 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    sig::Signal,
+    tempo::{Division, Transport},
+    white::White,
+};
+use std::f64::consts::{PI, TAU};
+
+/// Waveform of an [`Lfo`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LfoShape {
+    /// Sine wave
+    Sine,
+    /// Triangle wave
+    Triangle,
+    /// Rising sawtooth wave
+    Saw,
+    /// Square wave
+    Square,
+    /// New random level each cycle (sample & hold of noise)
+    SampleHold,
+    /// Random levels each cycle, smoothly interpolated between
+    SmoothRandom,
+}
+
+/// Low frequency oscillator, for use as a modulation source.
+#[derive(Clone, Debug)]
+pub struct Lfo {
+    sample_rate: f64,
+    shape: LfoShape,
+    unipolar: bool,
+    offset: f64,
+    // Phase (0 to 1)
+    phase: f64,
+    white: White,
+    // Random levels of the last cycle and this cycle
+    last: f64,
+    held: f64,
+}
+
+impl Lfo {
+    /// Create a new bipolar (-1 to 1) LFO.
+    pub fn new<R: Into<f64>>(sample_rate: R, shape: LfoShape) -> Self {
+        let mut white = White::new();
+        let last = white.noise().into();
+        let held = white.noise().into();
+        Self {
+            sample_rate: sample_rate.into(),
+            shape,
+            unipolar: false,
+            offset: 0.0,
+            phase: 0.0,
+            white,
+            last,
+            held,
+        }
+    }
+
+    /// Change the seed for the random shapes, and pick new random levels
+    /// (every LFO starts with the same seed, so stacked random LFOs move
+    /// together unless they're seeded differently).
+    #[inline(always)]
+    pub fn seed(&mut self, seed: u64) {
+        self.white = White::with_seed(seed);
+        self.last = self.white.noise().into();
+        self.held = self.white.noise().into();
+    }
+
+    /// Change the waveform.
+    #[inline(always)]
+    pub fn shape(&mut self, shape: LfoShape) {
+        self.shape = shape;
+    }
+
+    /// Set whether output is unipolar (0 to 1) rather than bipolar (-1 to 1).
+    #[inline(always)]
+    pub fn unipolar(&mut self, unipolar: bool) {
+        self.unipolar = unipolar;
+    }
+
+    /// Set the phase offset (fraction of a cycle) - range: 0~1
+    #[inline(always)]
+    pub fn offset(&mut self, offset: f64) {
+        self.offset = offset.rem_euclid(1.0);
+    }
+
+    /// Restart the cycle (from the phase offset).
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+    }
+
+    /// Generate the next sample of the LFO.
+    ///
+    /// - `rate`: Frequency in Hz
+    #[inline(always)]
+    pub fn gen(&mut self, rate: f64) -> Signal {
+        self.cycle(self.phase + rate / self.sample_rate)
+    }
+
+    /// Generate the next sample of the LFO, phase-locked to a [`Transport`]
+    /// with one cycle per `division`.
+    #[inline(always)]
+    pub fn sync(
+        &mut self,
+        transport: &Transport,
+        division: Division,
+    ) -> Signal {
+        let phase = transport.phase(division);
+        // Starting a new cycle
+        let phase = if phase < self.phase {
+            phase + 1.0
+        } else {
+            phase
+        };
+        self.cycle(phase)
+    }
+
+    /// Move to a new phase (1 or more to start a new cycle), and generate the
+    /// sample.
+    #[inline(always)]
+    fn cycle(&mut self, phase: f64) -> Signal {
+        if phase >= 1.0 {
+            self.last = self.held;
+            self.held = self.white.noise().into();
+        }
+        self.phase = phase.fract();
+        let t = (self.phase + self.offset).fract();
+        let out = match self.shape {
+            LfoShape::Sine => (TAU * t).sin(),
+            LfoShape::Triangle => 1.0 - 4.0 * ((t + 0.25).fract() - 0.5).abs(),
+            LfoShape::Saw => t * 2.0 - 1.0,
+            LfoShape::Square => {
+                if t < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            LfoShape::SampleHold => self.held,
+            LfoShape::SmoothRandom => {
+                // Cosine interpolation
+                let t = 0.5 - 0.5 * (PI * self.phase).cos();
+                self.last + (self.held - self.last) * t
+            }
+        };
+        if self.unipolar {
+            ((out + 1.0) * 0.5).into()
+        } else {
+            out.into()
+        }
+    }
+}
@@ -92,14 +92,179 @@
     variant_size_differences
 )]
 
+mod additive;
+mod adsr;
+mod allpass;
+mod arp;
+mod automation;
+mod bend;
+mod biquad;
+mod blue;
+mod brown;
+mod chaos;
+mod chip;
+mod chord;
+mod chorus;
+mod comb;
+mod compressor;
+mod convolution;
+mod crossover;
+mod crusher;
+mod curve;
+mod dahdsr;
+mod dc;
+mod deesser;
+mod delay;
+mod drift;
+mod envelope;
+mod eq;
+mod euclid;
+mod exciter;
+mod fdn;
+mod fft;
+mod flanger;
+mod fm;
+mod fold;
+mod follower;
+mod formant;
+mod freeverb;
+mod freqshift;
+mod gate;
+mod glide;
+mod hold;
+mod ladder;
+mod leslie;
+mod lfo;
+mod limiter;
+mod line;
+mod matrix;
+mod midi;
+mod modal;
+mod mono;
+mod multiband;
+mod note;
+mod phaser;
+mod pingpong;
 mod pink;
+mod pitch;
+mod pluck;
+mod poly;
+mod retrigger;
 mod room;
+mod scale;
+mod seq;
+mod shaper;
+mod shelf;
 mod sig;
+mod smf;
+mod smooth;
+mod smoothed;
+mod stepmod;
+mod stretch;
+mod svf;
 mod synth;
+mod tape;
+mod tempo;
+mod tilt;
+mod track;
+mod tract;
+mod tremolo;
+mod unison;
+mod vector;
+mod velocity;
+mod vibrato;
+mod violet;
+mod vocoder;
+mod vowel;
+mod wah;
+mod waveguide;
+mod wavetable;
 mod white;
+mod widener;
 
+pub use additive::Additive;
+pub use adsr::Adsr;
+pub use allpass::{Allpass, Allpass1};
+pub use arp::{ArpMode, Arpeggiator};
+pub use automation::Automation;
+pub use bend::PitchBend;
+pub use biquad::{Biquad, BiquadMode};
+pub use blue::Blue;
+pub use brown::Brown;
+pub use chaos::{Logistic, Lorenz};
+pub use chip::{Duty, Lfsr};
+pub use chord::{Chord, ChordQuality};
+pub use chorus::Chorus;
+pub use comb::{Comb, CombMode};
+pub use compressor::Compressor;
+pub use convolution::Convolution;
+pub use crossover::Crossover;
+pub use crusher::Crusher;
+pub use dahdsr::Dahdsr;
+pub use dc::DcBlock;
+pub use deesser::DeEsser;
+pub use delay::Delay;
+pub use drift::Drift;
+pub use envelope::Envelope;
+pub use eq::Equalizer;
+pub use euclid::Euclid;
+pub use exciter::Exciter;
+pub use fdn::{Fdn, FdnMatrix};
+pub use flanger::Flanger;
+pub use fm::{Fm, Operator};
+pub use fold::Fold;
+pub use follower::Follower;
+pub use formant::Formant;
+pub use freeverb::Freeverb;
+pub use freqshift::FreqShift;
+pub use gate::Gate;
+pub use glide::Glide;
+pub use hold::SampleHold;
+pub use ladder::Ladder;
+pub use leslie::Leslie;
+pub use lfo::{Lfo, LfoShape};
+pub use limiter::Limiter;
+pub use matrix::ModMatrix;
+pub use midi::{Midi, MidiMessage};
+pub use modal::Modal;
+pub use mono::{Mono, NotePriority};
+pub use multiband::Multiband;
+pub use note::Note;
+pub use phaser::Phaser;
+pub use pingpong::PingPong;
 pub use pink::Pink;
+pub use pitch::PitchShift;
+pub use pluck::Pluck;
+pub use poly::{Expression, Poly, Steal, Voice};
+pub use retrigger::Retrigger;
 pub use room::Room;
+pub use scale::{Quantizer, Scale};
+pub use seq::{Sequencer, Step};
+pub use shaper::Waveshaper;
+pub use shelf::{Shelf, ShelfMode};
 pub use sig::Signal;
+pub use smf::MidiFile;
+pub use smooth::Smooth;
+pub use smoothed::Smoothed;
+pub use stepmod::StepMod;
+pub use stretch::PhaseVocoder;
+pub use svf::{Svf, SvfOutput};
 pub use synth::{Fc, Mix, Synth};
+pub use tape::TapeDelay;
+pub use tempo::{Division, Groove, Transport};
+pub use tilt::Tilt;
+pub use track::KeyTrack;
+pub use tract::Tract;
+pub use tremolo::Tremolo;
+pub use unison::Unison;
+pub use vector::Vector;
+pub use velocity::Velocity;
+pub use vibrato::Vibrato;
+pub use violet::Violet;
+pub use vocoder::Vocoder;
+pub use vowel::Vowel;
+pub use wah::AutoWah;
+pub use waveguide::{Junction, Termination, Waveguide};
+pub use wavetable::{Interp, Wavetable};
 pub use white::White;
+pub use widener::Widener;
@@ -0,0 +1,117 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{compressor::from_db, line::Line, sig::Signal};
+
+/// Lookahead brickwall limiter.  Delays the signal so that the gain can be
+/// smoothly reduced before each peak arrives, keeping the estimated true
+/// (inter-sample) peak level at or below the ceiling.
+#[derive(Clone, Debug)]
+pub struct Limiter {
+    line: Line,
+    // Target gains over the lookahead window (ring buffer)
+    targets: Vec<f64>,
+    // Released gains over the lookahead window (ring buffer)
+    envelope: Vec<f64>,
+    // Index of the newest values in `targets` and `envelope`
+    write: usize,
+    // Sum of `envelope`
+    sum: f64,
+    // Released gain
+    gain: f64,
+    release: f64,
+    // Last four input samples, oldest first
+    history: [f64; 4],
+}
+
+impl Limiter {
+    /// Create a new limiter.
+    ///
+    /// - `lookahead`: Time in seconds to look ahead for peaks (also the
+    ///   latency)
+    /// - `release`: Time in seconds to recover after a peak
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        lookahead: f64,
+        release: f64,
+    ) -> Self {
+        let sample_rate = sample_rate.into();
+        let window = ((lookahead * sample_rate).round() as usize).max(1);
+        let release = if release > 0.0 {
+            (-1.0 / (release * sample_rate)).exp()
+        } else {
+            0.0
+        };
+        Self {
+            line: Line::new(window),
+            targets: vec![1.0; window],
+            envelope: vec![1.0; window],
+            write: 0,
+            sum: window as f64,
+            gain: 1.0,
+            release,
+            history: [0.0; 4],
+        }
+    }
+
+    /// Get the latency of the output in samples.
+    #[inline(always)]
+    pub fn latency(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `ceiling`: Maximum true peak level in dB (for example, -1.0 for
+    ///   -1 dBTP)
+    #[inline(always)]
+    pub fn process(&mut self, input: Signal, ceiling: f64) -> Signal {
+        let x = f64::from(input);
+        let ceiling = from_db(ceiling);
+        self.history.rotate_left(1);
+        self.history[3] = x;
+        // Gain needed to keep the peak between the middle samples in check
+        let target = (ceiling / self.peak()).min(1.0);
+        let window = self.targets.len();
+        self.write = (self.write + 1) % window;
+        self.targets[self.write] = target;
+        let held = self.targets.iter().cloned().fold(1.0, f64::min);
+        self.gain = if held < self.gain {
+            held
+        } else {
+            held + self.release * (self.gain - held)
+        };
+        // Moving average turns each gain drop into a ramp over the window
+        self.sum += self.gain - self.envelope[self.write];
+        self.envelope[self.write] = self.gain;
+        let gain = (self.sum / window as f64).min(1.0);
+        let delayed = self.line.read(window as f64);
+        self.line.push(x);
+        (delayed * gain).into()
+    }
+
+    /// Estimate the true peak between the two middle samples of the history
+    /// by 4x oversampling with cubic interpolation.
+    #[inline(always)]
+    fn peak(&self) -> f64 {
+        let [a, b, c, d] = self.history;
+        let mut peak = b.abs().max(c.abs());
+        for t in [0.25, 0.5, 0.75].iter() {
+            // Catmull-Rom spline
+            let y = b + 0.5
+                * t
+                * (c - a
+                    + t * (2.0 * a - 5.0 * b + 4.0 * c - d
+                        + t * (3.0 * (b - c) + d - a)));
+            peak = peak.max(y.abs());
+        }
+        peak.max(f64::MIN_POSITIVE)
+    }
+}
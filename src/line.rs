@@ -0,0 +1,53 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+/// Ring buffer delay line with fractional (linearly interpolated) reads.
+#[derive(Clone, Debug)]
+pub(crate) struct Line {
+    buffer: Vec<f64>,
+    write: usize,
+}
+
+impl Line {
+    /// Create a delay line that can delay up to `max` samples.
+    pub(crate) fn new(max: usize) -> Self {
+        Self {
+            buffer: vec![0.0; max.max(1) + 1],
+            write: 0,
+        }
+    }
+
+    /// Maximum delay in samples.
+    #[inline(always)]
+    pub(crate) fn max(&self) -> usize {
+        self.buffer.len() - 1
+    }
+
+    /// Read the sample pushed `delay` samples ago (1 is the last sample
+    /// pushed), interpolating between samples.
+    #[inline(always)]
+    pub(crate) fn read(&self, delay: f64) -> f64 {
+        let len = self.buffer.len();
+        let delay = delay.clamp(1.0, self.max() as f64);
+        let pos = self.write as f64 + len as f64 - delay;
+        let i = pos as usize;
+        let frac = pos.fract();
+        let a = self.buffer[i % len];
+        let b = self.buffer[(i + 1) % len];
+        a + (b - a) * frac
+    }
+
+    /// Push a new sample into the delay line.
+    #[inline(always)]
+    pub(crate) fn push(&mut self, sample: f64) {
+        self.buffer[self.write] = sample;
+        self.write = (self.write + 1) % self.buffer.len();
+    }
+}
@@ -0,0 +1,97 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+
+/// Modulation matrix.  Routes named modulation sources (LFOs, envelopes,
+/// velocity, …) to named destinations (pitch, cutoff, gain, …), each route
+/// with it's own depth.
+///
+/// Each sample, set the value of every source with
+/// [`set()`](ModMatrix::set), then read the summed modulation of each
+/// destination with [`get()`](ModMatrix::get).
+#[derive(Clone, Debug, Default)]
+pub struct ModMatrix {
+    // Name and current value of each source
+    sources: Vec<(String, f64)>,
+    destinations: Vec<String>,
+    // Source, destination and depth of each route
+    routes: Vec<(usize, usize, f64)>,
+}
+
+impl ModMatrix {
+    /// Create a new empty modulation matrix.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a modulation source, returning it's index.
+    pub fn source(&mut self, name: &str) -> usize {
+        self.sources.push((name.to_string(), 0.0));
+        self.sources.len() - 1
+    }
+
+    /// Add a modulation destination, returning it's index.
+    pub fn destination(&mut self, name: &str) -> usize {
+        self.destinations.push(name.to_string());
+        self.destinations.len() - 1
+    }
+
+    /// Look up the index of a source by name.
+    pub fn find_source(&self, name: &str) -> Option<usize> {
+        self.sources.iter().position(|(n, _)| n == name)
+    }
+
+    /// Look up the index of a destination by name.
+    pub fn find_destination(&self, name: &str) -> Option<usize> {
+        self.destinations.iter().position(|n| n == name)
+    }
+
+    /// Route a source to a destination, returning the index of the route.
+    ///
+    /// - `depth`: Amount of the source added to the destination (negative to
+    ///   invert)
+    pub fn route(
+        &mut self,
+        source: usize,
+        destination: usize,
+        depth: f64,
+    ) -> usize {
+        assert!(source < self.sources.len(), "No such source");
+        assert!(destination < self.destinations.len(), "No such destination");
+        self.routes.push((source, destination, depth));
+        self.routes.len() - 1
+    }
+
+    /// Change the depth of route number `route`.
+    #[inline(always)]
+    pub fn depth(&mut self, route: usize, depth: f64) {
+        self.routes[route].2 = depth;
+    }
+
+    /// Set the current value of source number `source`.
+    #[inline(always)]
+    pub fn set(&mut self, source: usize, value: Signal) {
+        self.sources[source].1 = value.into();
+    }
+
+    /// Get the sum of all modulation routed to destination number
+    /// `destination`.
+    #[inline(always)]
+    pub fn get(&self, destination: usize) -> Signal {
+        self.routes
+            .iter()
+            .filter(|route| route.1 == destination)
+            .map(|(source, _, depth)| self.sources[*source].1 * depth)
+            .sum::<f64>()
+            .into()
+    }
+}
@@ -0,0 +1,481 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    note::Note,
+    poly::{Expression, Voice},
+    velocity::Velocity,
+};
+
+/// Data entry (MSB) controller number
+const DATA_ENTRY: u8 = 6;
+/// Sustain pedal controller number
+const SUSTAIN: u8 = 64;
+/// Timbre (MPE) controller number
+const TIMBRE: u8 = 74;
+/// Registered parameter number (LSB and MSB) controller numbers
+const RPN_LSB: u8 = 100;
+const RPN_MSB: u8 = 101;
+/// MPE configuration registered parameter number
+const RPN_MPE: u16 = 6;
+
+/// A MIDI channel voice message.  Channels are numbered 0~15.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MidiMessage {
+    /// A key was released.
+    NoteOff {
+        /// MIDI channel
+        channel: u8,
+        /// MIDI note number (0~127)
+        note: u8,
+        /// Release velocity (0~127)
+        velocity: u8,
+    },
+    /// A key was pressed.
+    NoteOn {
+        /// MIDI channel
+        channel: u8,
+        /// MIDI note number (0~127)
+        note: u8,
+        /// Velocity (1~127)
+        velocity: u8,
+    },
+    /// Pressure on a single key changed (polyphonic aftertouch).
+    KeyPressure {
+        /// MIDI channel
+        channel: u8,
+        /// MIDI note number (0~127)
+        note: u8,
+        /// Pressure (0~127)
+        pressure: u8,
+    },
+    /// A controller (knob, fader, pedal, …) changed.
+    ControlChange {
+        /// MIDI channel
+        channel: u8,
+        /// Controller number (0~127)
+        controller: u8,
+        /// Controller value (0~127)
+        value: u8,
+    },
+    /// Pressure on the keyboard changed (channel aftertouch).
+    ChannelPressure {
+        /// MIDI channel
+        channel: u8,
+        /// Pressure (0~127)
+        pressure: u8,
+    },
+    /// The pitch bend wheel moved.
+    PitchBend {
+        /// MIDI channel
+        channel: u8,
+        /// Pitch bend (-8192~8191, 0 for centered)
+        bend: i16,
+    },
+}
+
+impl MidiMessage {
+    /// Parse a MIDI message from raw bytes (as passed to a `midir` input
+    /// callback).  Returns `None` for messages that aren't channel voice
+    /// messages this enum covers (program change, system messages), or are
+    /// too short.  Note on with velocity 0 is parsed as note off.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let status = *bytes.first()?;
+        let channel = status & 0x0F;
+        let data = |i: usize| bytes.get(i).map(|byte| byte & 0x7F);
+        Some(match status & 0xF0 {
+            0x80 => MidiMessage::NoteOff {
+                channel,
+                note: data(1)?,
+                velocity: data(2)?,
+            },
+            0x90 => match data(2)? {
+                0 => MidiMessage::NoteOff {
+                    channel,
+                    note: data(1)?,
+                    velocity: 64,
+                },
+                velocity => MidiMessage::NoteOn {
+                    channel,
+                    note: data(1)?,
+                    velocity,
+                },
+            },
+            0xA0 => MidiMessage::KeyPressure {
+                channel,
+                note: data(1)?,
+                pressure: data(2)?,
+            },
+            0xB0 => MidiMessage::ControlChange {
+                channel,
+                controller: data(1)?,
+                value: data(2)?,
+            },
+            0xD0 => MidiMessage::ChannelPressure {
+                channel,
+                pressure: data(1)?,
+            },
+            0xE0 => MidiMessage::PitchBend {
+                channel,
+                bend: (i16::from(data(2)?) << 7 | i16::from(data(1)?)) - 8192,
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// MIDI input.  Parses MIDI messages, plays the notes on a [`Voice`] (such as
+/// a [`Poly`](crate::Poly)), sends pitch bend to all of its notes with
+/// [`Voice::bend()`], and keeps track of the latest controller, pitch bend and
+/// pressure values for the synthesizer to read.  Holding the sustain
+/// pedal (controller 64) delays note offs until it's let go.
+///
+/// Supports MPE (MIDI Polyphonic Expression), where each note is played on
+/// its own member channel of a zone (with [`Voice::note_on_channel()`]), and
+/// the pitch bend, pressure and timbre (controller 74) of that channel are
+/// sent to the voice playing the note as an [`Expression`] (with
+/// [`Voice::express_channel()`]).  Messages on a zone's master channel
+/// (channel 0 for the lower zone, channel 15 for the upper zone) apply to all
+/// notes.  Zones are set with [`mpe()`](Midi::mpe), or by the controller with
+/// an MPE configuration message.
+#[derive(Clone, Debug)]
+pub struct Midi {
+    controllers: [u8; 128],
+    bend: i16,
+    pressure: u8,
+    // Notes released while the sustain pedal was down (with their MPE member
+    // channel)
+    sustained: Vec<(Option<u8>, Note)>,
+    // Number of member channels in the lower and upper MPE zones
+    zones: [u8; 2],
+    // Note and expression of each MPE member channel
+    notes: [Option<Note>; 16],
+    expressions: [Expression; 16],
+    // Selected registered parameter number of each channel
+    rpns: [u16; 16],
+}
+
+impl Midi {
+    /// Create a new MIDI input (listening on all channels).
+    pub fn new() -> Self {
+        Self {
+            controllers: [0; 128],
+            bend: 0,
+            pressure: 0,
+            sustained: Vec::new(),
+            zones: [0; 2],
+            notes: [None; 16],
+            expressions: [Expression::default(); 16],
+            rpns: [0x3FFF; 16],
+        }
+    }
+
+    /// Set up the MPE zones (turned off by default).
+    ///
+    /// - `lower`: Number of member channels in the lower zone, channels 1 and
+    ///   up - range: 0~15 (0 for no lower zone)
+    /// - `upper`: Number of member channels in the upper zone, channels 14 and
+    ///   down - range: 0~15 (0 for no upper zone)
+    pub fn mpe(&mut self, lower: u8, upper: u8) {
+        self.zones = [lower.min(15), upper.min(15)];
+        // The lower zone takes priority when zones overlap
+        self.zones[1] = self.zones[1].min(14 - self.zones[0].min(14));
+    }
+
+    /// Returns true if a channel is an MPE member channel.
+    #[inline(always)]
+    fn member(&self, channel: u8) -> bool {
+        let [lower, upper] = self.zones;
+        (channel >= 1 && channel <= lower)
+            || (channel <= 14 && channel >= 15 - upper)
+    }
+
+    /// Handle a MIDI message from raw bytes, sending note events to `voice`.
+    /// Returns the parsed message (if valid).
+    pub fn input<V: Voice>(
+        &mut self,
+        bytes: &[u8],
+        voice: &mut V,
+    ) -> Option<MidiMessage> {
+        let message = MidiMessage::parse(bytes)?;
+        self.message(message, voice);
+        Some(message)
+    }
+
+    /// Handle a parsed MIDI message, sending note events to `voice`.
+    pub fn message<V: Voice>(&mut self, message: MidiMessage, voice: &mut V) {
+        match message {
+            MidiMessage::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => {
+                let note = Note::from_midi(note);
+                let velocity = Velocity::from_midi(velocity);
+                let member = Some(channel).filter(|c| self.member(*c));
+                self.sustained.retain(|held| *held != (member, note));
+                if let Some(channel) = member {
+                    let expression = self.expressions[usize::from(channel)];
+                    self.notes[usize::from(channel)] = Some(note);
+                    voice.note_on_channel(channel, note, velocity);
+                    voice.express_channel(channel, note, expression);
+                } else {
+                    voice.note_on(note, velocity);
+                }
+            }
+            MidiMessage::NoteOff { channel, note, .. } => {
+                let note = Note::from_midi(note);
+                let member = Some(channel).filter(|c| self.member(*c));
+                if let Some(channel) = member {
+                    self.notes[usize::from(channel)] = None;
+                }
+                if self.sustain() {
+                    self.sustained.push((member, note));
+                } else {
+                    Self::release(voice, member, note);
+                }
+            }
+            MidiMessage::ControlChange {
+                channel,
+                controller,
+                value,
+            } => self.control(channel, controller, value, voice),
+            MidiMessage::ChannelPressure { channel, pressure } => {
+                if self.member(channel) {
+                    let pressure = f64::from(pressure) / 127.0;
+                    self.express(channel, voice, |e| e.pressure = pressure);
+                } else {
+                    self.pressure = pressure;
+                }
+            }
+            MidiMessage::PitchBend { channel, bend } => {
+                if self.member(channel) {
+                    let bend = f64::from(bend) / 8192.0;
+                    self.express(channel, voice, |e| e.bend = bend);
+                } else {
+                    self.bend = bend;
+                    voice.bend(self.bend());
+                }
+            }
+            MidiMessage::KeyPressure { .. } => {}
+        }
+    }
+
+    /// Handle a control change message.
+    fn control<V: Voice>(
+        &mut self,
+        channel: u8,
+        controller: u8,
+        value: u8,
+        voice: &mut V,
+    ) {
+        let rpn = &mut self.rpns[usize::from(channel)];
+        match controller {
+            RPN_MSB => *rpn = (*rpn & 0x7F) | u16::from(value) << 7,
+            RPN_LSB => *rpn = (*rpn & !0x7F) | u16::from(value),
+            DATA_ENTRY if *rpn == RPN_MPE => match channel {
+                0 => self.mpe(value, self.zones[1]),
+                15 => self.mpe(self.zones[0].min(14 - value.min(14)), value),
+                _ => {}
+            },
+            _ => {}
+        }
+        if self.member(channel) {
+            if controller == TIMBRE {
+                let timbre = f64::from(value) / 127.0;
+                self.express(channel, voice, |e| e.timbre = timbre);
+            }
+            return;
+        }
+        self.controllers[usize::from(controller)] = value;
+        if controller == SUSTAIN && !self.sustain() {
+            for (member, note) in self.sustained.drain(..) {
+                Self::release(voice, member, note);
+            }
+        }
+    }
+
+    /// Release a note, on its MPE member channel if it has one.
+    fn release<V: Voice>(voice: &mut V, member: Option<u8>, note: Note) {
+        match member {
+            Some(channel) => voice.note_off_channel(channel, note),
+            None => voice.note_off(note),
+        }
+    }
+
+    /// Change the expression of an MPE member channel.
+    fn express<V: Voice>(
+        &mut self,
+        channel: u8,
+        voice: &mut V,
+        change: impl FnOnce(&mut Expression),
+    ) {
+        let index = usize::from(channel);
+        change(&mut self.expressions[index]);
+        if let Some(note) = self.notes[index] {
+            voice.express_channel(channel, note, self.expressions[index]);
+        }
+    }
+
+    /// Get the value of a controller - range: 0~1
+    #[inline(always)]
+    pub fn cc(&self, controller: u8) -> f64 {
+        f64::from(self.controllers[usize::from(controller & 0x7F)]) / 127.0
+    }
+
+    /// Get the pitch bend - range: -1~1
+    #[inline(always)]
+    pub fn bend(&self) -> f64 {
+        f64::from(self.bend) / 8192.0
+    }
+
+    /// Get the channel pressure (aftertouch) - range: 0~1
+    #[inline(always)]
+    pub fn pressure(&self) -> f64 {
+        f64::from(self.pressure) / 127.0
+    }
+
+    /// Returns true while the sustain pedal is held down.
+    #[inline(always)]
+    pub fn sustain(&self) -> bool {
+        self.controllers[usize::from(SUSTAIN)] >= 64
+    }
+}
+
+impl Default for Midi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            MidiMessage::parse(&[0x91, 60, 100]),
+            Some(MidiMessage::NoteOn {
+                channel: 1,
+                note: 60,
+                velocity: 100
+            })
+        );
+        // Note on with velocity 0 is note off
+        assert_eq!(
+            MidiMessage::parse(&[0x90, 60, 0]),
+            Some(MidiMessage::NoteOff {
+                channel: 0,
+                note: 60,
+                velocity: 64
+            })
+        );
+        assert_eq!(
+            MidiMessage::parse(&[0xE0, 0x00, 0x40]),
+            Some(MidiMessage::PitchBend {
+                channel: 0,
+                bend: 0
+            })
+        );
+        assert_eq!(
+            MidiMessage::parse(&[0xE0, 0x7F, 0x7F]),
+            Some(MidiMessage::PitchBend {
+                channel: 0,
+                bend: 8191
+            })
+        );
+        // Data bytes are masked to 7 bits
+        assert_eq!(
+            MidiMessage::parse(&[0xD2, 0xFF]),
+            Some(MidiMessage::ChannelPressure {
+                channel: 2,
+                pressure: 0x7F
+            })
+        );
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert_eq!(MidiMessage::parse(&[]), None);
+        for status in [0x80, 0x90, 0xA0, 0xB0, 0xE0].iter() {
+            assert_eq!(MidiMessage::parse(&[*status]), None);
+            assert_eq!(MidiMessage::parse(&[*status, 60]), None);
+        }
+        assert_eq!(MidiMessage::parse(&[0xD0]), None);
+    }
+
+    /// Voice that records its expression, and if it's held.
+    #[derive(Clone, Debug, Default)]
+    struct Probe {
+        held: bool,
+        expression: Expression,
+    }
+
+    impl Voice for Probe {
+        fn note_on(&mut self, _note: Note, _velocity: Velocity) {
+            self.held = true;
+        }
+
+        fn note_off(&mut self, _note: Note) {
+            self.held = false;
+        }
+
+        fn is_idle(&self) -> bool {
+            !self.held
+        }
+
+        fn gen(&mut self, _fc: crate::Fc) -> crate::Signal {
+            0.0.into()
+        }
+
+        fn express(&mut self, _note: Note, expression: Expression) {
+            self.expression = expression;
+        }
+    }
+
+    #[test]
+    fn mpe_same_note() {
+        let mut midi = Midi::new();
+        midi.mpe(15, 0);
+        for steal in [crate::Steal::Oldest, crate::Steal::SameNote].iter() {
+            let mut poly = crate::Poly::<_, 4>::new(Probe::default(), *steal);
+            // Same note on member channels 1 and 2
+            midi.input(&[0x91, 60, 100], &mut poly);
+            midi.input(&[0x92, 60, 100], &mut poly);
+            midi.input(&[0xE1, 0x00, 0x60], &mut poly);
+            midi.input(&[0xD2, 127], &mut poly);
+            let voices = poly.voices();
+            assert!(voices[0].held && voices[1].held);
+            assert_eq!(voices[0].expression.bend, 0.5);
+            assert_eq!(voices[0].expression.pressure, 0.0);
+            assert_eq!(voices[1].expression.bend, 0.0);
+            assert_eq!(voices[1].expression.pressure, 1.0);
+            // Releasing channel 1 leaves channel 2 playing
+            midi.input(&[0x81, 60, 64], &mut poly);
+            let voices = poly.voices();
+            assert!(!voices[0].held && voices[1].held);
+            midi.input(&[0x82, 60, 64], &mut poly);
+            assert!(poly.is_idle());
+            midi.input(&[0xE1, 0x00, 0x40], &mut poly);
+            midi.input(&[0xD2, 0], &mut poly);
+        }
+    }
+
+    #[test]
+    fn parse_unsupported() {
+        // Program change, system exclusive, timing clock
+        assert_eq!(MidiMessage::parse(&[0xC0, 5]), None);
+        assert_eq!(MidiMessage::parse(&[0xF0, 0x7E, 0xF7]), None);
+        assert_eq!(MidiMessage::parse(&[0xF8]), None);
+        // Data byte without a status byte
+        assert_eq!(MidiMessage::parse(&[60, 100]), None);
+    }
+}
@@ -0,0 +1,74 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+use std::f64::consts::TAU;
+
+#[derive(Copy, Clone, Debug)]
+struct Mode {
+    // Feedback coefficients
+    a1: f64,
+    a2: f64,
+    // Input gain
+    b0: f64,
+    // Previous outputs
+    y1: f64,
+    y2: f64,
+}
+
+/// Modal resonator bank.  A set of tuned, damped resonators excited by an
+/// input signal, for modeling bells, bars, and membranes.
+#[derive(Clone, Debug)]
+pub struct Modal {
+    modes: Vec<Mode>,
+    sample_rate: f64,
+}
+
+impl Modal {
+    /// Create a new modal resonator bank with no modes.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        Self {
+            modes: Vec::new(),
+            sample_rate: sample_rate.into(),
+        }
+    }
+
+    /// Add a resonant mode.
+    ///
+    /// - `freq`: Frequency of the mode
+    /// - `decay`: Time in seconds for the mode to decay by 60 dB
+    /// - `gain`: Amplitude of the mode when excited by an impulse
+    pub fn mode(&mut self, freq: f64, decay: f64, gain: f64) {
+        let w = TAU * freq / self.sample_rate;
+        let r = 10.0f64.powf(-3.0 / (decay * self.sample_rate));
+        self.modes.push(Mode {
+            a1: 2.0 * r * w.cos(),
+            a2: -r * r,
+            b0: gain * w.sin(),
+            y1: 0.0,
+            y2: 0.0,
+        });
+    }
+
+    /// Excite the resonators with an input signal, and get the next sample.
+    #[inline(always)]
+    pub fn filter(&mut self, input: Signal) -> Signal {
+        let input = f64::from(input);
+        let mut out = 0.0;
+        for mode in self.modes.iter_mut() {
+            let y = mode.b0 * input + mode.a1 * mode.y1 + mode.a2 * mode.y2;
+            mode.y2 = mode.y1;
+            mode.y1 = y;
+            out += y;
+        }
+        out.into()
+    }
+}
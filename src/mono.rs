@@ -0,0 +1,238 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    note::Note,
+    poly::{Expression, Voice},
+    sig::Signal,
+    synth::Fc,
+    velocity::Velocity,
+};
+
+/// Which of the held notes a [`Mono`] plays.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NotePriority {
+    /// The most recently pressed note.
+    Last,
+    /// The lowest note.
+    Low,
+    /// The highest note.
+    High,
+}
+
+/// Monophonic voice mode, for bass and lead instruments.  Plays one note at a
+/// time on a [`Voice`], chosen from the held notes by [`NotePriority`].
+/// Letting go of the playing note goes back to the next note still held
+/// down.
+///
+/// In legato mode, changing notes while a note is held calls
+/// [`Voice::legato()`] instead of restarting the note, so that the voice can
+/// keep its envelopes going (and glide, with a [`Glide`](crate::Glide)).
+///
+/// ```rust
+/// use twang::{Adsr, Fc, Mono, Note, NotePriority, Signal, Velocity, Voice};
+///
+/// #[derive(Clone, Debug)]
+/// struct Bass {
+///     freq: f64,
+///     adsr: Adsr,
+/// }
+///
+/// impl Voice for Bass {
+///     fn note_on(&mut self, note: Note, _velocity: Velocity) {
+///         self.freq = note.freq();
+///         self.adsr.note_on();
+///     }
+///
+///     fn note_off(&mut self, _note: Note) {
+///         self.adsr.note_off();
+///     }
+///
+///     fn is_idle(&self) -> bool {
+///         self.adsr.is_idle()
+///     }
+///
+///     fn gen(&mut self, fc: Fc) -> Signal {
+///         fc.saw_bl(self.freq).gain(self.adsr.gen()).gain(0.5)
+///     }
+///
+///     // Change pitch without restarting the envelope
+///     fn legato(&mut self, _from: Note, note: Note, _velocity: Velocity) {
+///         self.freq = note.freq();
+///     }
+/// }
+///
+/// let adsr = Adsr::new(48_000, 0.01, 0.1, 0.7, 0.3);
+/// let bass = Bass { freq: 0.0, adsr };
+/// let mut mono = Mono::new(bass, NotePriority::Low, true);
+/// mono.note_on(Note::from_midi(40), Velocity::default());
+/// // Still plays note 40 (lowest note priority)
+/// mono.note_on(Note::from_midi(47), Velocity::default());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Mono<T: Voice> {
+    voice: T,
+    priority: NotePriority,
+    legato: bool,
+    // Notes held down, in the order they were pressed
+    held: Vec<(Note, Velocity)>,
+    // Note the voice is playing
+    playing: Option<Note>,
+}
+
+impl<T: Voice> Mono<T> {
+    /// Create a new monophonic voice.
+    ///
+    /// - `priority`: Which held note to play
+    /// - `legato`: Change notes with [`Voice::legato()`] while a note is
+    ///   held, rather than restarting the note
+    pub fn new(voice: T, priority: NotePriority, legato: bool) -> Self {
+        Self {
+            voice,
+            priority,
+            legato,
+            held: Vec::new(),
+            playing: None,
+        }
+    }
+
+    /// Get the voice (for changing its parameters).
+    #[inline(always)]
+    pub fn voice(&mut self) -> &mut T {
+        &mut self.voice
+    }
+
+    /// Change the note priority (takes effect at the next note).
+    #[inline(always)]
+    pub fn priority(&mut self, priority: NotePriority) {
+        self.priority = priority;
+    }
+
+    /// Turn legato mode on or off.
+    #[inline(always)]
+    pub fn legato(&mut self, legato: bool) {
+        self.legato = legato;
+    }
+
+    /// Press a note.
+    pub fn note_on(&mut self, note: Note, velocity: Velocity) {
+        self.held.retain(|(held, _)| *held != note);
+        self.held.push((note, velocity));
+        self.play();
+    }
+
+    /// Let go of a note, going back to the next held note if it was playing.
+    pub fn note_off(&mut self, note: Note) {
+        self.held.retain(|(held, _)| *held != note);
+        if self.playing != Some(note) {
+            return;
+        }
+        if self.held.is_empty() {
+            self.playing = None;
+            self.voice.note_off(note);
+        } else {
+            self.play();
+        }
+    }
+
+    /// Change the expression of the note if it's playing.
+    pub fn express(&mut self, note: Note, expression: Expression) {
+        if self.playing == Some(note) {
+            self.voice.express(note, expression);
+        }
+    }
+
+    /// Change the pitch bend - range: -1~1
+    #[inline(always)]
+    pub fn bend(&mut self, bend: f64) {
+        self.voice.bend(bend);
+    }
+
+    /// Let go of all notes.
+    pub fn release_all(&mut self) {
+        self.held.clear();
+        if let Some(note) = self.playing.take() {
+            self.voice.note_off(note);
+        }
+    }
+
+    /// Returns true when the voice is silent.
+    #[inline(always)]
+    pub fn is_idle(&self) -> bool {
+        self.playing.is_none() && self.voice.is_idle()
+    }
+
+    /// Generate the next sample.
+    #[inline(always)]
+    pub fn gen(&mut self, fc: Fc) -> Signal {
+        self.voice.gen(fc)
+    }
+
+    /// Play the held note with the highest priority, if it isn't already.
+    fn play(&mut self) {
+        let pick = match self.priority {
+            NotePriority::Last => self.held.last(),
+            NotePriority::Low => self.held.iter().min_by(|a, b| {
+                a.0.number().partial_cmp(&b.0.number()).expect("NaN note")
+            }),
+            NotePriority::High => self.held.iter().max_by(|a, b| {
+                a.0.number().partial_cmp(&b.0.number()).expect("NaN note")
+            }),
+        };
+        let (note, velocity) = match pick {
+            Some(pick) => *pick,
+            None => return,
+        };
+        match self.playing {
+            Some(playing) if playing == note => return,
+            Some(playing) if self.legato => {
+                self.voice.legato(playing, note, velocity)
+            }
+            Some(playing) => {
+                self.voice.note_off(playing);
+                self.voice.note_on(note, velocity);
+            }
+            None => self.voice.note_on(note, velocity),
+        }
+        self.playing = Some(note);
+    }
+}
+
+impl<T: Voice> Voice for Mono<T> {
+    #[inline(always)]
+    fn note_on(&mut self, note: Note, velocity: Velocity) {
+        Mono::note_on(self, note, velocity);
+    }
+
+    #[inline(always)]
+    fn note_off(&mut self, note: Note) {
+        Mono::note_off(self, note);
+    }
+
+    #[inline(always)]
+    fn is_idle(&self) -> bool {
+        Mono::is_idle(self)
+    }
+
+    #[inline(always)]
+    fn gen(&mut self, fc: Fc) -> Signal {
+        Mono::gen(self, fc)
+    }
+
+    #[inline(always)]
+    fn express(&mut self, note: Note, expression: Expression) {
+        Mono::express(self, note, expression);
+    }
+
+    #[inline(always)]
+    fn bend(&mut self, bend: f64) {
+        Mono::bend(self, bend);
+    }
+}
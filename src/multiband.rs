@@ -0,0 +1,71 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{compressor::Compressor, crossover::Crossover, sig::Signal};
+
+/// Three band compressor.  Splits a signal with a [`Crossover`], and
+/// compresses the low, mid and high bands independently before summing them.
+#[derive(Copy, Clone, Debug)]
+pub struct Multiband {
+    crossover: Crossover,
+    // Compressor, threshold and ratio for each band
+    bands: [(Compressor, f64, f64); 3],
+}
+
+impl Multiband {
+    /// Create a new multiband compressor, with compression on all bands
+    /// initially off (ratio of 1).
+    ///
+    /// - `low`: Crossover frequency in Hz between the low and mid bands
+    /// - `high`: Crossover frequency in Hz between the mid and high bands
+    pub fn new<R: Into<f64>>(sample_rate: R, low: f64, high: f64) -> Self {
+        let sample_rate = sample_rate.into();
+        let band = (Compressor::new(sample_rate, 0.01, 0.1), 0.0, 1.0);
+        Self {
+            crossover: Crossover::new(sample_rate, low, high),
+            bands: [band; 3],
+        }
+    }
+
+    /// Change the settings of band number `band` (0 for low, 1 for mid, 2 for
+    /// high).
+    ///
+    /// - `threshold`: Level in dB above which gain is reduced
+    /// - `ratio`: Input to output level ratio above the threshold - range: 1+
+    /// - `attack`: Time in seconds to react to a rising level
+    /// - `release`: Time in seconds to recover from a falling level
+    pub fn set(
+        &mut self,
+        band: usize,
+        threshold: f64,
+        ratio: f64,
+        attack: f64,
+        release: f64,
+    ) {
+        let band = &mut self.bands[band];
+        band.0.time(attack, release);
+        band.1 = threshold;
+        band.2 = ratio;
+    }
+
+    /// Process the next sample of a signal.
+    #[inline(always)]
+    pub fn process(&mut self, input: Signal) -> Signal {
+        let split = self.crossover.split(input);
+        let mut out = 0.0;
+        for (band, (compressor, threshold, ratio)) in
+            split.iter().zip(self.bands.iter_mut())
+        {
+            let band = compressor.process(*band, *threshold, *ratio, 0.0, 0.0);
+            out += f64::from(band);
+        }
+        out.into()
+    }
+}
@@ -0,0 +1,46 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+/// A musical note, as a MIDI note number (69 is A4, 440 Hz).  Can be
+/// fractional for microtonal pitches.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Note(f64);
+
+impl Note {
+    /// Create a new note from a (possibly fractional) MIDI note number.
+    #[inline(always)]
+    pub fn new(number: f64) -> Self {
+        Self(number)
+    }
+
+    /// Create a new note from a MIDI note number (0~127).
+    #[inline(always)]
+    pub fn from_midi(number: u8) -> Self {
+        Self(number.into())
+    }
+
+    /// Create a new note from a frequency in Hz.
+    #[inline(always)]
+    pub fn from_freq(freq: f64) -> Self {
+        Self(69.0 + 12.0 * (freq / 440.0).log2())
+    }
+
+    /// Get the MIDI note number.
+    #[inline(always)]
+    pub fn number(self) -> f64 {
+        self.0
+    }
+
+    /// Get the frequency of the note in Hz (equal temperament).
+    #[inline(always)]
+    pub fn freq(self) -> f64 {
+        440.0 * ((self.0 - 69.0) / 12.0).exp2()
+    }
+}
@@ -0,0 +1,74 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{allpass::Allpass1, sig::Signal};
+use std::f64::consts::TAU;
+
+/// Lowest break frequency of the allpass stages in Hz.
+const MIN_FREQ: f64 = 100.0;
+/// Number of octaves swept at full depth.
+const OCTAVES: f64 = 6.0;
+
+/// Phaser effect.  A chain of first-order allpass stages with their break
+/// frequency swept by an LFO, mixed with the dry signal to create moving
+/// notches.
+#[derive(Clone, Debug)]
+pub struct Phaser {
+    stages: Vec<Allpass1>,
+    sample_rate: f64,
+    // LFO phase (0 to 1)
+    phase: f64,
+    // Last output of the allpass chain, for feedback
+    last: f64,
+}
+
+impl Phaser {
+    /// Create a new phaser effect.
+    ///
+    /// - `stages`: Number of allpass stages (each pair adds a notch)
+    pub fn new<R: Into<f64>>(sample_rate: R, stages: usize) -> Self {
+        Self {
+            stages: vec![Allpass1::new(); stages],
+            sample_rate: sample_rate.into(),
+            phase: 0.0,
+            last: 0.0,
+        }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `rate`: LFO frequency in Hz
+    /// - `depth`: Amount of break frequency sweep - range: 0~1
+    /// - `feedback`: Gain of the allpass chain output fed back into it's input
+    ///   - range: -1~1 (exclusive)
+    /// - `mix`: Wet/dry mix - range: 0~1 (0.5 for the deepest notches)
+    #[inline(always)]
+    pub fn process(
+        &mut self,
+        input: Signal,
+        rate: f64,
+        depth: f64,
+        feedback: f64,
+        mix: f64,
+    ) -> Signal {
+        let x = f64::from(input);
+        self.phase = (self.phase + rate / self.sample_rate).fract();
+        let lfo = (1.0 - (TAU * self.phase).cos()) * 0.5;
+        let freq = (MIN_FREQ * (OCTAVES * depth.clamp(0.0, 1.0) * lfo).exp2())
+            .min(self.sample_rate * 0.49);
+        let coeff = Allpass1::coeff(self.sample_rate, freq);
+        let mut wet = Signal::from(x + self.last * feedback);
+        for stage in self.stages.iter_mut() {
+            wet = stage.filter(wet, coeff);
+        }
+        self.last = wet.into();
+        (x * (1.0 - mix) + self.last * mix).into()
+    }
+}
@@ -0,0 +1,57 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{line::Line, sig::Signal};
+
+/// Ping-pong stereo delay.  Echoes alternate between the left and right
+/// channels, with stereo signals as `[left, right]`.
+#[derive(Clone, Debug)]
+pub struct PingPong {
+    lines: [Line; 2],
+}
+
+impl PingPong {
+    /// Create a new ping-pong delay effect.
+    ///
+    /// - `max_time`: The maximum delay time (of each channel) in seconds
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R, max_time: f64) -> Self {
+        let max = (max_time * sample_rate.into()).ceil() as usize + 1;
+        Self {
+            lines: [Line::new(max), Line::new(max)],
+        }
+    }
+
+    /// Process the next stereo sample.  The input is summed to mono, and
+    /// enters the left channel first.
+    ///
+    /// - `delay`: The `[left, right]` delay times in samples
+    /// - `feedback`: Gain of each repeat crossing to the other channel - range:
+    ///   0~1 (exclusive)
+    /// - `mix`: Wet/dry mix - range: 0~1 (0.0 for dry only, 1.0 for wet only)
+    #[inline(always)]
+    pub fn process(
+        &mut self,
+        input: [Signal; 2],
+        delay: [f64; 2],
+        feedback: f64,
+        mix: f64,
+    ) -> [Signal; 2] {
+        let [l, r] = [f64::from(input[0]), f64::from(input[1])];
+        let left = self.lines[0].read(delay[0]);
+        let right = self.lines[1].read(delay[1]);
+        self.lines[0].push((l + r) * 0.5 + right * feedback);
+        self.lines[1].push(left * feedback);
+        [
+            (l * (1.0 - mix) + left * mix).into(),
+            (r * (1.0 - mix) + right * mix).into(),
+        ]
+    }
+}
@@ -175,7 +175,7 @@ impl Pink {
             8 => self.e(),
             _ => unreachable!(),
         } as f64
-            / (std::i16::MAX as f64);
+            / (i16::MAX as f64);
         self.which += 1;
         self.which %= 16;
         Signal::from(r)
@@ -0,0 +1,57 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{line::Line, sig::Signal};
+use std::f64::consts::PI;
+
+/// Real-time pitch shifter.  Transposes a signal without changing it's
+/// duration by reading a delay line with two taps sweeping at a different
+/// speed than it's written, crossfading between them (overlap-add).
+#[derive(Clone, Debug)]
+pub struct PitchShift {
+    line: Line,
+    // Window size in samples
+    window: f64,
+    // Position of the first tap within the window (0 to 1)
+    phase: f64,
+}
+
+impl PitchShift {
+    /// Create a new pitch shifter.
+    ///
+    /// - `window`: Length of each grain in seconds (longer is smoother, but
+    ///   smears transients) - for example, 0.05
+    pub fn new<R: Into<f64>>(sample_rate: R, window: f64) -> Self {
+        let window = (window * sample_rate.into()).max(2.0);
+        Self {
+            line: Line::new(window.ceil() as usize + 2),
+            window,
+            phase: 0.0,
+        }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `semitones`: Transposition (fractional for cents, 0.01 = 1 cent)
+    #[inline(always)]
+    pub fn process(&mut self, input: Signal, semitones: f64) -> Signal {
+        let ratio = (semitones / 12.0).exp2();
+        self.phase = (self.phase + (1.0 - ratio) / self.window).rem_euclid(1.0);
+        self.line.push(input.into());
+        let mut out = 0.0;
+        for offset in [0.0, 0.5].iter() {
+            let phase = (self.phase + offset).fract();
+            // Squared sine windows of both taps sum to 1
+            let gain = (PI * phase).sin().powi(2);
+            out += self.line.read(1.0 + phase * self.window) * gain;
+        }
+        out.into()
+    }
+}
@@ -0,0 +1,86 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{sig::Signal, white::White};
+
+/// Lowest frequency that can be plucked.
+const MIN_FREQ: f64 = 20.0;
+
+/// Plucked string using the Karplus-Strong algorithm.  A burst of noise is fed
+/// into a tuned feedback delay with damping.
+#[derive(Clone, Debug)]
+pub struct Pluck {
+    buffer: Vec<f64>,
+    write: usize,
+    delay: f64,
+    gain: f64,
+    damping: f64,
+    last: f64,
+    sample_rate: f64,
+    white: White,
+}
+
+impl Pluck {
+    /// Create a new plucked string (silent until plucked).
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        let sample_rate = sample_rate.into();
+        Self {
+            buffer: vec![0.0; (sample_rate / MIN_FREQ) as usize + 2],
+            write: 0,
+            delay: 1.0,
+            gain: 0.0,
+            damping: 0.5,
+            last: 0.0,
+            sample_rate,
+            white: White::new(),
+        }
+    }
+
+    /// Pluck the string.
+    ///
+    /// - `freq`: Pitch of the string (20 Hz or higher)
+    /// - `decay`: Time in seconds for the string to decay by 60 dB
+    /// - `brightness`: Amount of high frequencies - range: 0~1
+    pub fn pluck(&mut self, freq: f64, decay: f64, brightness: f64) {
+        let freq = freq.max(MIN_FREQ);
+        let brightness = brightness.clamp(0.0, 1.0);
+        let len = self.buffer.len();
+        let period = self.sample_rate / freq;
+        self.damping = 0.5 * (1.0 - brightness);
+        self.delay = (period - self.damping).max(1.0);
+        self.gain = 10.0f64.powf(-3.0 / (decay * freq));
+        // Fill one period of the delay line with (filtered) noise.
+        let mut prev = 0.0;
+        for i in 0..period.ceil() as usize {
+            let noise: f64 = self.white.noise().into();
+            prev = brightness * noise + (1.0 - brightness) * prev;
+            self.buffer[(self.write + len - 1 - i % len) % len] = prev;
+        }
+        self.last = 0.0;
+    }
+
+    /// Generate the next sample of the string.
+    #[inline(always)]
+    pub fn gen(&mut self) -> Signal {
+        let len = self.buffer.len();
+        let pos = self.write as f64 + len as f64 - self.delay;
+        let i = pos as usize;
+        let frac = pos.fract();
+        let a = self.buffer[i % len];
+        let b = self.buffer[(i + 1) % len];
+        let sample = a + (b - a) * frac;
+        let out = self.gain
+            * ((1.0 - self.damping) * sample + self.damping * self.last);
+        self.last = sample;
+        self.buffer[self.write] = out;
+        self.write = (self.write + 1) % len;
+        out.into()
+    }
+}
@@ -0,0 +1,572 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{note::Note, sig::Signal, synth::Fc, velocity::Velocity};
+
+/// Per-note expression, from an MPE (MIDI Polyphonic Expression) controller.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct Expression {
+    /// Pitch bend - range: -1~1
+    pub bend: f64,
+    /// Pressure (aftertouch) - range: 0~1
+    pub pressure: f64,
+    /// Timbre (controller 74, usually sliding up and down the key) - range:
+    /// 0~1
+    pub timbre: f64,
+}
+
+/// A sound that can be played with notes, such as one voice of a [`Poly`].
+pub trait Voice {
+    /// Start playing a note.
+    fn note_on(&mut self, note: Note, velocity: Velocity);
+
+    /// Release a note (a voice of a [`Poly`] only gets the note off for the
+    /// note it's playing).
+    fn note_off(&mut self, note: Note);
+
+    /// Returns true once the voice is silent (the release has finished), so
+    /// that it can be reused.
+    fn is_idle(&self) -> bool;
+
+    /// Generate the next sample.
+    fn gen(&mut self, fc: Fc) -> Signal;
+
+    /// Change the expression of a note that's held down (a voice of a
+    /// [`Poly`] only gets the expression for the note it's playing).  Does
+    /// nothing by default.
+    fn express(&mut self, _note: Note, _expression: Expression) {}
+
+    /// Start playing a note on an MPE member channel.  Notes on different
+    /// channels are kept apart even when they're the same pitch.  Starts the
+    /// note with [`note_on()`](Voice::note_on) by default.
+    fn note_on_channel(
+        &mut self,
+        _channel: u8,
+        note: Note,
+        velocity: Velocity,
+    ) {
+        self.note_on(note, velocity);
+    }
+
+    /// Release a note started on an MPE member channel.  Releases the note
+    /// with [`note_off()`](Voice::note_off) by default.
+    fn note_off_channel(&mut self, _channel: u8, note: Note) {
+        self.note_off(note);
+    }
+
+    /// Change the expression of a note started on an MPE member channel.
+    /// Changes it with [`express()`](Voice::express) by default.
+    fn express_channel(
+        &mut self,
+        _channel: u8,
+        note: Note,
+        expression: Expression,
+    ) {
+        self.express(note, expression);
+    }
+
+    /// Change the pitch bend of all notes - range: -1~1.  Use a
+    /// [`PitchBend`](crate::PitchBend) to apply it.  Does nothing by default.
+    fn bend(&mut self, _bend: f64) {}
+
+    /// Change from one held note to another without restarting it (legato
+    /// playing in a [`Mono`](crate::Mono)).  By default, releases `from` and
+    /// starts `note`.
+    fn legato(&mut self, from: Note, note: Note, velocity: Velocity) {
+        self.note_off(from);
+        self.note_on(note, velocity);
+    }
+}
+
+/// Which voice a [`Poly`] takes for a new note when none of them are idle.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Steal {
+    /// Take the voice that started its note the longest time ago (released
+    /// notes first).
+    Oldest,
+    /// Take the voice that is currently the quietest.
+    Quietest,
+    /// Take the voice already playing the same note if there is one (even
+    /// before idle voices, so repeated notes don't stack up), otherwise the
+    /// oldest.
+    SameNote,
+    /// Take the voice with the lowest priority (see
+    /// [`Poly::note_on_priority()`]), released notes first - ties go to the
+    /// oldest.
+    Priority,
+}
+
+/// Polyphonic voice allocator.  Owns `N` copies of a [`Voice`], gives each
+/// new note a free voice (stealing one according to [`Steal`] when they're
+/// all in use), and mixes the voices together.
+///
+/// ```rust
+/// use twang::{Adsr, Fc, Note, Poly, Signal, Steal, Velocity, Voice};
+///
+/// #[derive(Clone, Debug)]
+/// struct Saw {
+///     freq: f64,
+///     gain: f64,
+///     adsr: Adsr,
+/// }
+///
+/// impl Voice for Saw {
+///     fn note_on(&mut self, note: Note, velocity: Velocity) {
+///         self.freq = note.freq();
+///         self.gain = velocity.value();
+///         self.adsr.note_on();
+///     }
+///
+///     fn note_off(&mut self, _note: Note) {
+///         self.adsr.note_off();
+///     }
+///
+///     fn is_idle(&self) -> bool {
+///         self.adsr.is_idle()
+///     }
+///
+///     fn gen(&mut self, fc: Fc) -> Signal {
+///         fc.saw_bl(self.freq).gain(self.adsr.gen()).gain(self.gain * 0.2)
+///     }
+/// }
+///
+/// let adsr = Adsr::new(48_000, 0.01, 0.1, 0.7, 0.3);
+/// let saw = Saw { freq: 0.0, gain: 0.0, adsr };
+/// let mut poly = Poly::<_, 8>::new(saw, Steal::Oldest);
+/// // A minor chord
+/// for note in [57, 60, 64].iter() {
+///     poly.note_on(Note::from_midi(*note), Velocity::default());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Poly<T: Voice, const N: usize> {
+    voices: [T; N],
+    steal: Steal,
+    // Note held down on each voice
+    held: [Option<Note>; N],
+    // MPE member channel of the note held down on each voice
+    channels: [Option<u8>; N],
+    // Note each voice is playing (or last played)
+    notes: [Option<Note>; N],
+    // When each voice was last given a note
+    ages: [u64; N],
+    priorities: [u8; N],
+    // Mean square of each voice's output
+    levels: [f64; N],
+    clock: u64,
+}
+
+impl<T: Voice + Clone, const N: usize> Poly<T, N> {
+    /// Create a new polyphonic voice allocator with `N` copies of `voice`.
+    pub fn new(voice: T, steal: Steal) -> Self {
+        Self {
+            voices: std::array::from_fn(|_| voice.clone()),
+            steal,
+            held: [None; N],
+            channels: [None; N],
+            notes: [None; N],
+            ages: [0; N],
+            priorities: [0; N],
+            levels: [0.0; N],
+            clock: 0,
+        }
+    }
+}
+
+impl<T: Voice, const N: usize> Poly<T, N> {
+    /// Get the voices (for changing their parameters).
+    #[inline(always)]
+    pub fn voices(&mut self) -> &mut [T; N] {
+        &mut self.voices
+    }
+
+    /// Start playing a note, returning the index of the voice playing it.
+    ///
+    /// # Panics
+    /// If `N` is 0.
+    #[inline(always)]
+    pub fn note_on(&mut self, note: Note, velocity: Velocity) -> usize {
+        self.note_on_priority(note, velocity, 0)
+    }
+
+    /// Start playing a note with a priority, returning the index of the voice
+    /// playing it.  With [`Steal::Priority`], notes with a higher priority
+    /// are kept over lower priority notes (for example, to keep the melody
+    /// and bass playing over the inner voices of chords).
+    ///
+    /// # Panics
+    /// If `N` is 0.
+    pub fn note_on_priority(
+        &mut self,
+        note: Note,
+        velocity: Velocity,
+        priority: u8,
+    ) -> usize {
+        self.start(note, velocity, priority, None)
+    }
+
+    /// Release every voice holding a note.
+    pub fn note_off(&mut self, note: Note) {
+        for (voice, held) in self.voices.iter_mut().zip(self.held.iter_mut()) {
+            if *held == Some(note) {
+                *held = None;
+                voice.note_off(note);
+            }
+        }
+    }
+
+    /// Start playing a note on an MPE member channel, returning the index of
+    /// the voice playing it.  Only
+    /// [`note_off_channel()`](Poly::note_off_channel) and
+    /// [`express_channel()`](Poly::express_channel) for the same channel go
+    /// to this voice, so notes of the same pitch on different channels stay
+    /// apart.
+    ///
+    /// # Panics
+    /// If `N` is 0.
+    pub fn note_on_channel(
+        &mut self,
+        channel: u8,
+        note: Note,
+        velocity: Velocity,
+    ) -> usize {
+        self.start(note, velocity, 0, Some(channel))
+    }
+
+    /// Release the voice holding a note on an MPE member channel.
+    pub fn note_off_channel(&mut self, channel: u8, note: Note) {
+        for i in 0..N {
+            if self.held[i] == Some(note) && self.channels[i] == Some(channel) {
+                self.held[i] = None;
+                self.voices[i].note_off(note);
+            }
+        }
+    }
+
+    /// Change the expression of the voice holding a note on an MPE member
+    /// channel.
+    pub fn express_channel(
+        &mut self,
+        channel: u8,
+        note: Note,
+        expression: Expression,
+    ) {
+        for i in 0..N {
+            if self.held[i] == Some(note) && self.channels[i] == Some(channel) {
+                self.voices[i].express(note, expression);
+            }
+        }
+    }
+
+    /// Change the expression of every voice holding a note.
+    pub fn express(&mut self, note: Note, expression: Expression) {
+        for (voice, held) in self.voices.iter_mut().zip(self.held.iter()) {
+            if *held == Some(note) {
+                voice.express(note, expression);
+            }
+        }
+    }
+
+    /// Change the pitch bend of every voice (including released voices, so
+    /// that their tails bend too) - range: -1~1
+    pub fn bend(&mut self, bend: f64) {
+        for voice in self.voices.iter_mut() {
+            voice.bend(bend);
+        }
+    }
+
+    /// Change the note of every voice holding `from` without restarting it.
+    /// Starts `note` on a new voice if no voices are holding `from`.
+    pub fn legato(&mut self, from: Note, note: Note, velocity: Velocity) {
+        let mut moved = false;
+        for i in 0..N {
+            if self.held[i] == Some(from) {
+                self.held[i] = Some(note);
+                self.notes[i] = Some(note);
+                self.voices[i].legato(from, note, velocity);
+                moved = true;
+            }
+        }
+        if !moved {
+            self.note_on(note, velocity);
+        }
+    }
+
+    /// Release all held notes.
+    pub fn release_all(&mut self) {
+        for (voice, held) in self.voices.iter_mut().zip(self.held.iter_mut()) {
+            if let Some(note) = held.take() {
+                voice.note_off(note);
+            }
+        }
+    }
+
+    /// Returns true when no voices are sounding.
+    pub fn is_idle(&self) -> bool {
+        self.voices.iter().all(|voice| voice.is_idle())
+    }
+
+    /// Generate the next sample (the sum of all sounding voices).
+    #[inline(always)]
+    pub fn gen(&mut self, fc: Fc) -> Signal {
+        // Level measured over about 50 milliseconds
+        let period = fc.period();
+        let amount = period / (period + 0.05);
+        let mut out = 0.0;
+        for i in 0..N {
+            if self.held[i].is_some() || !self.voices[i].is_idle() {
+                let sample = f64::from(self.voices[i].gen(fc));
+                self.levels[i] += (sample * sample - self.levels[i]) * amount;
+                out += sample;
+            } else {
+                self.levels[i] = 0.0;
+            }
+        }
+        out.into()
+    }
+
+    /// Start playing a note on a new voice, returning the index of the voice.
+    fn start(
+        &mut self,
+        note: Note,
+        velocity: Velocity,
+        priority: u8,
+        channel: Option<u8>,
+    ) -> usize {
+        let voice = self.allocate(note, channel);
+        self.clock += 1;
+        self.held[voice] = Some(note);
+        self.channels[voice] = channel;
+        self.notes[voice] = Some(note);
+        self.ages[voice] = self.clock;
+        self.priorities[voice] = priority;
+        self.voices[voice].note_on(note, velocity);
+        voice
+    }
+
+    /// Pick a voice for a new note (on an MPE member channel).
+    fn allocate(&self, note: Note, channel: Option<u8>) -> usize {
+        let released = |i: usize| self.held[i].is_none();
+        let sounding = |i: usize| !released(i) || !self.voices[i].is_idle();
+        // Voice passing the filter that sorts first by the key
+        let pick = |filter: &dyn Fn(usize) -> bool,
+                    key: &dyn Fn(usize) -> (f64, u64)| {
+            (0..N).filter(|i| filter(*i)).min_by(|a, b| {
+                key(*a).partial_cmp(&key(*b)).expect("NaN voice level")
+            })
+        };
+        let oldest = |i: usize| (0.0, self.ages[i]);
+        if self.steal == Steal::SameNote {
+            // Held notes on other MPE member channels are kept apart
+            let same = |i: usize| {
+                self.notes[i] == Some(note)
+                    && sounding(i)
+                    && (released(i) || self.channels[i] == channel)
+            };
+            if let Some(voice) = pick(&same, &oldest) {
+                return voice;
+            }
+        }
+        if let Some(voice) = pick(&|i| !sounding(i), &oldest) {
+            return voice;
+        }
+        match self.steal {
+            Steal::Oldest | Steal::SameNote => {
+                pick(&released, &oldest).or_else(|| pick(&|_| true, &oldest))
+            }
+            Steal::Quietest => {
+                pick(&|_| true, &|i| (self.levels[i], self.ages[i]))
+            }
+            Steal::Priority => pick(&released, &oldest).or_else(|| {
+                pick(&|_| true, &|i| (self.priorities[i].into(), self.ages[i]))
+            }),
+        }
+        .expect("Poly has no voices")
+    }
+}
+
+impl<T: Voice, const N: usize> Voice for Poly<T, N> {
+    #[inline(always)]
+    fn note_on(&mut self, note: Note, velocity: Velocity) {
+        Poly::note_on(self, note, velocity);
+    }
+
+    #[inline(always)]
+    fn note_off(&mut self, note: Note) {
+        Poly::note_off(self, note);
+    }
+
+    #[inline(always)]
+    fn is_idle(&self) -> bool {
+        Poly::is_idle(self)
+    }
+
+    #[inline(always)]
+    fn gen(&mut self, fc: Fc) -> Signal {
+        Poly::gen(self, fc)
+    }
+
+    #[inline(always)]
+    fn express(&mut self, note: Note, expression: Expression) {
+        Poly::express(self, note, expression);
+    }
+
+    #[inline(always)]
+    fn note_on_channel(&mut self, channel: u8, note: Note, velocity: Velocity) {
+        Poly::note_on_channel(self, channel, note, velocity);
+    }
+
+    #[inline(always)]
+    fn note_off_channel(&mut self, channel: u8, note: Note) {
+        Poly::note_off_channel(self, channel, note);
+    }
+
+    #[inline(always)]
+    fn express_channel(
+        &mut self,
+        channel: u8,
+        note: Note,
+        expression: Expression,
+    ) {
+        Poly::express_channel(self, channel, note, expression);
+    }
+
+    #[inline(always)]
+    fn bend(&mut self, bend: f64) {
+        Poly::bend(self, bend);
+    }
+
+    #[inline(always)]
+    fn legato(&mut self, from: Note, note: Note, velocity: Velocity) {
+        Poly::legato(self, from, note, velocity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Synth;
+    use fon::Stream;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Event {
+        On(Note),
+        Off(Note),
+        Legato(Note, Note),
+    }
+
+    /// Voice that records what it's told to play, and outputs its velocity
+    /// while held.
+    #[derive(Clone, Debug, Default)]
+    struct Recorder {
+        level: Option<f64>,
+        events: Vec<Event>,
+    }
+
+    impl Voice for Recorder {
+        fn note_on(&mut self, note: Note, velocity: Velocity) {
+            self.level = Some(velocity.value());
+            self.events.push(Event::On(note));
+        }
+
+        fn note_off(&mut self, note: Note) {
+            self.level = None;
+            self.events.push(Event::Off(note));
+        }
+
+        fn legato(&mut self, from: Note, note: Note, _velocity: Velocity) {
+            self.events.push(Event::Legato(from, note));
+        }
+
+        fn is_idle(&self) -> bool {
+            self.level.is_none()
+        }
+
+        fn gen(&mut self, _fc: Fc) -> Signal {
+            self.level.unwrap_or(0.0).into()
+        }
+    }
+
+    /// Play 100 milliseconds of audio, so that voice levels are measured.
+    fn play<const N: usize>(poly: Poly<Recorder, N>) -> Poly<Recorder, N> {
+        let mut synth = Synth::new(poly, |poly, fc| poly.gen(fc));
+        let mut stream = &mut synth;
+        stream.set_sample_rate(1_000.0);
+        Iterator::take(stream, 100).for_each(drop);
+        synth.params().clone()
+    }
+
+    fn note(number: u8) -> Note {
+        Note::from_midi(number)
+    }
+
+    #[test]
+    fn steal_oldest() {
+        let mut poly = Poly::<_, 2>::new(Recorder::default(), Steal::Oldest);
+        let loud = Velocity::new(1.0);
+        assert_eq!(poly.note_on(note(60), loud), 0);
+        assert_eq!(poly.note_on(note(62), loud), 1);
+        assert_eq!(poly.note_on(note(64), loud), 0);
+        // Released voices are free before any are stolen
+        poly.note_off(note(62));
+        assert_eq!(poly.note_on(note(65), loud), 1);
+        poly.legato(note(65), note(67), loud);
+        assert_eq!(
+            poly.voices()[0].events,
+            [Event::On(note(60)), Event::On(note(64)),]
+        );
+        assert_eq!(
+            poly.voices()[1].events,
+            [
+                Event::On(note(62)),
+                Event::Off(note(62)),
+                Event::On(note(65)),
+                Event::Legato(note(65), note(67)),
+            ]
+        );
+    }
+
+    #[test]
+    fn steal_quietest() {
+        let mut poly = Poly::<_, 2>::new(Recorder::default(), Steal::Quietest);
+        assert_eq!(poly.note_on(note(60), Velocity::new(1.0)), 0);
+        assert_eq!(poly.note_on(note(62), Velocity::new(0.25)), 1);
+        let mut poly = play(poly);
+        // Newer, but quieter
+        assert_eq!(poly.note_on(note(64), Velocity::new(1.0)), 1);
+        assert_eq!(poly.voices()[0].events, [Event::On(note(60))]);
+        assert_eq!(
+            poly.voices()[1].events,
+            [Event::On(note(62)), Event::On(note(64)),]
+        );
+    }
+
+    #[test]
+    fn steal_lowest_priority() {
+        let mut poly = Poly::<_, 3>::new(Recorder::default(), Steal::Priority);
+        let loud = Velocity::new(1.0);
+        // Melody, inner voice and bass
+        assert_eq!(poly.note_on_priority(note(72), loud, 2), 0);
+        assert_eq!(poly.note_on_priority(note(64), loud, 0), 1);
+        assert_eq!(poly.note_on_priority(note(48), loud, 1), 2);
+        assert_eq!(poly.note_on_priority(note(74), loud, 2), 1);
+        // Bass is now the lowest priority
+        assert_eq!(poly.note_on_priority(note(67), loud, 1), 2);
+        assert_eq!(poly.voices()[0].events, [Event::On(note(72))]);
+        assert_eq!(
+            poly.voices()[1].events,
+            [Event::On(note(64)), Event::On(note(74)),]
+        );
+        assert_eq!(
+            poly.voices()[2].events,
+            [Event::On(note(48)), Event::On(note(67)),]
+        );
+    }
+}
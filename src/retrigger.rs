@@ -0,0 +1,22 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+/// What an envelope does when a new note starts while it's still playing.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Retrigger {
+    /// Restart from a level of 0 (sharp, may click).
+    Always,
+    /// Restart from the current level (the default).
+    #[default]
+    FromCurrent,
+    /// Keep going if the previous note is still held, otherwise restart from
+    /// the current level.
+    Legato,
+}
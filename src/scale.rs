@@ -0,0 +1,141 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::note::Note;
+
+/// Musical scale, for a [`Quantizer`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Scale {
+    /// All twelve semitones
+    Chromatic,
+    /// Major (Ionian)
+    Major,
+    /// Natural minor (Aeolian)
+    Minor,
+    /// Harmonic minor
+    HarmonicMinor,
+    /// Melodic minor (ascending)
+    MelodicMinor,
+    /// Dorian mode
+    Dorian,
+    /// Phrygian mode
+    Phrygian,
+    /// Lydian mode
+    Lydian,
+    /// Mixolydian mode
+    Mixolydian,
+    /// Locrian mode
+    Locrian,
+    /// Major pentatonic
+    MajorPentatonic,
+    /// Minor pentatonic
+    MinorPentatonic,
+    /// Blues (minor pentatonic with a ♭5)
+    Blues,
+    /// Whole tone
+    WholeTone,
+}
+
+impl Scale {
+    /// Get the intervals above the root in semitones (including the root).
+    pub fn intervals(self) -> &'static [u8] {
+        use Scale::*;
+        match self {
+            Chromatic => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+            Major => &[0, 2, 4, 5, 7, 9, 11],
+            Minor => &[0, 2, 3, 5, 7, 8, 10],
+            HarmonicMinor => &[0, 2, 3, 5, 7, 8, 11],
+            MelodicMinor => &[0, 2, 3, 5, 7, 9, 11],
+            Dorian => &[0, 2, 3, 5, 7, 9, 10],
+            Phrygian => &[0, 1, 3, 5, 7, 8, 10],
+            Lydian => &[0, 2, 4, 6, 7, 9, 11],
+            Mixolydian => &[0, 2, 4, 5, 7, 9, 10],
+            Locrian => &[0, 1, 3, 5, 6, 8, 10],
+            MajorPentatonic => &[0, 2, 4, 7, 9],
+            MinorPentatonic => &[0, 3, 5, 7, 10],
+            Blues => &[0, 3, 5, 6, 7, 10],
+            WholeTone => &[0, 2, 4, 6, 8, 10],
+        }
+    }
+}
+
+/// Pitch quantizer.  Snaps any pitch (for example, from a random modulation
+/// source) to the nearest note of a scale, so generative melodies stay in
+/// key.
+///
+/// ```rust
+/// use twang::{Note, Quantizer, Scale};
+///
+/// // A minor pentatonic
+/// let quantizer = Quantizer::new(Note::from_midi(57), Scale::MinorPentatonic);
+/// assert_eq!(quantizer.quantize(Note::new(65.4)).number(), 64.0);
+/// assert_eq!(quantizer.quantize(Note::new(66.0)).number(), 67.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quantizer {
+    // Pitch class of the root (0 to 12)
+    root: f64,
+    // Semitones above the root of each note in the scale, plus the root an
+    // octave up
+    degrees: Vec<f64>,
+}
+
+impl Quantizer {
+    /// Create a new quantizer for a scale.  Only the pitch class of the root
+    /// matters (which octave doesn't).
+    pub fn new(root: Note, scale: Scale) -> Self {
+        let degrees = scale.intervals().iter().map(|i| f64::from(*i));
+        Self::with_degrees(root, degrees.collect())
+    }
+
+    /// Create a new quantizer for a custom scale (for microtonal scales).
+    ///
+    /// - `intervals`: Semitones above the root of each note in the scale -
+    ///   range: 0~12 (the root is always included)
+    pub fn custom(root: Note, intervals: &[f64]) -> Self {
+        Self::with_degrees(root, intervals.to_vec())
+    }
+
+    /// Snap a note to the nearest note in the scale (ties go down).
+    pub fn quantize(&self, note: Note) -> Note {
+        let relative = note.number() - self.root;
+        let octave = (relative / 12.0).floor() * 12.0;
+        let pitch = relative - octave;
+        let distance = |degree: &f64| (pitch - degree).abs();
+        let nearest = self.degrees.iter().fold(0.0, |nearest, degree| {
+            if distance(degree) < distance(&nearest) {
+                *degree
+            } else {
+                nearest
+            }
+        });
+        Note::new(self.root + octave + nearest)
+    }
+
+    /// Snap a frequency in Hz to the frequency of the nearest note in the
+    /// scale.
+    #[inline(always)]
+    pub fn quantize_freq(&self, freq: f64) -> f64 {
+        self.quantize(Note::from_freq(freq)).freq()
+    }
+
+    /// Create a new quantizer from the degrees of a scale.
+    fn with_degrees(root: Note, mut degrees: Vec<f64>) -> Self {
+        degrees.retain(|degree| (0.0..12.0).contains(degree));
+        degrees.push(0.0);
+        degrees.push(12.0);
+        degrees.sort_by(|a, b| a.partial_cmp(b).expect("NaN interval"));
+        degrees.dedup();
+        Self {
+            root: root.number().rem_euclid(12.0),
+            degrees,
+        }
+    }
+}
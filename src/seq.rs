@@ -0,0 +1,443 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    note::Note,
+    poly::Voice,
+    sig::Signal,
+    synth::Fc,
+    tempo::{Division, Groove, Transport},
+    velocity::Velocity,
+    white::White,
+};
+
+/// One step of a [`Sequencer`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Step {
+    /// Note to play (`None` for a rest)
+    pub note: Option<Note>,
+    /// How hard to play the note
+    pub velocity: Velocity,
+    /// How long to hold the note, as a fraction of the step - range: 0~1
+    pub gate: f64,
+    /// Hold the note into the next step.  If the next step plays the same
+    /// note it keeps sounding, otherwise it changes to the next note with
+    /// [`Voice::legato()`].
+    pub tie: bool,
+    /// Chance of the note playing each time the step comes around - range:
+    /// 0~1
+    pub probability: f64,
+}
+
+impl Step {
+    /// Create a new step that always plays `note` at full velocity, held for
+    /// half of the step.
+    #[inline(always)]
+    pub fn new(note: Note) -> Self {
+        Self {
+            note: Some(note),
+            velocity: Velocity::default(),
+            gate: 0.5,
+            tie: false,
+            probability: 1.0,
+        }
+    }
+
+    /// Create a new rest step.
+    #[inline(always)]
+    pub fn rest() -> Self {
+        Self {
+            note: None,
+            ..Self::new(Note::new(0.0))
+        }
+    }
+}
+
+/// Note step sequencer.  Plays a looping pattern of [`Step`]s on a [`Voice`]
+/// (such as a [`Poly`](crate::Poly) or [`Mono`](crate::Mono)), one step at a
+/// time at a clocked rate (or following a [`Transport`]), with optional swing
+/// (see [`Groove`]).
+///
+/// ```rust
+/// use twang::{Adsr, Division, Fc, Note, Sequencer, Signal, Step, Velocity};
+/// use twang::Voice;
+///
+/// #[derive(Clone, Debug)]
+/// struct Saw {
+///     freq: f64,
+///     adsr: Adsr,
+/// }
+///
+/// impl Voice for Saw {
+///     fn note_on(&mut self, note: Note, _velocity: Velocity) {
+///         self.freq = note.freq();
+///         self.adsr.note_on();
+///     }
+///
+///     fn note_off(&mut self, _note: Note) {
+///         self.adsr.note_off();
+///     }
+///
+///     fn is_idle(&self) -> bool {
+///         self.adsr.is_idle()
+///     }
+///
+///     fn gen(&mut self, fc: Fc) -> Signal {
+///         fc.saw_bl(self.freq).gain(self.adsr.gen()).gain(0.3)
+///     }
+/// }
+///
+/// let adsr = Adsr::new(48_000, 0.005, 0.1, 0.5, 0.05);
+/// let saw = Saw { freq: 0.0, adsr };
+/// // Eighth notes at 120 BPM
+/// let rate = Division::new(1, 8).hz(120.0);
+/// let mut seq = Sequencer::new(saw, 48_000, rate);
+/// seq.step(Step::new(Note::from_midi(45)));
+/// seq.step(Step::rest());
+/// seq.step(Step { tie: true, ..Step::new(Note::from_midi(57)) });
+/// seq.step(Step { probability: 0.5, ..Step::new(Note::from_midi(55)) });
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sequencer<T: Voice> {
+    voice: T,
+    sample_rate: f64,
+    // Steps per second
+    rate: f64,
+    steps: Vec<Step>,
+    // Index of the current step (`None` before the first step)
+    current: Option<usize>,
+    groove: Groove,
+    // Straight position in steps from the start, when clocked by rate
+    position: f64,
+    // Progress through the current step (0 to 1)
+    phase: f64,
+    // Step number since the start of the current step
+    tick: Option<u64>,
+    // Note the voice is playing
+    playing: Option<Note>,
+    white: White,
+}
+
+impl<T: Voice> Sequencer<T> {
+    /// Create a new sequencer with no steps.
+    ///
+    /// - `rate`: Steps per second (see
+    ///   [`Division::hz()`](crate::Division::hz) for tempo sync)
+    pub fn new<R: Into<f64>>(voice: T, sample_rate: R, rate: f64) -> Self {
+        Self {
+            voice,
+            sample_rate: sample_rate.into(),
+            rate,
+            steps: Vec::new(),
+            current: None,
+            groove: Groove::straight(),
+            position: 0.0,
+            phase: 0.0,
+            tick: None,
+            playing: None,
+            white: White::new(),
+        }
+    }
+
+    /// Get the voice (for changing its parameters).
+    #[inline(always)]
+    pub fn voice(&mut self) -> &mut T {
+        &mut self.voice
+    }
+
+    /// Change the rate in steps per second.
+    #[inline(always)]
+    pub fn rate(&mut self, rate: f64) {
+        self.rate = rate;
+    }
+
+    /// Change the swing or groove timing.
+    #[inline(always)]
+    pub fn groove(&mut self, groove: Groove) {
+        self.groove = groove;
+    }
+
+    /// Change the seed for rolling step probabilities, so that sequencers
+    /// playing the same pattern don't skip the same steps.
+    #[inline(always)]
+    pub fn seed(&mut self, seed: u64) {
+        self.white = White::with_seed(seed);
+    }
+
+    /// Add a step to the end of the pattern, returning the index of the
+    /// step.
+    pub fn step(&mut self, step: Step) -> usize {
+        self.steps.push(step);
+        self.steps.len() - 1
+    }
+
+    /// Change step number `index`.
+    #[inline(always)]
+    pub fn set(&mut self, index: usize, step: Step) {
+        self.steps[index] = step;
+    }
+
+    /// Get the index of the step playing (`None` before the first step).
+    #[inline(always)]
+    pub fn current(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Release the playing note, and go back to before the first step.
+    pub fn reset(&mut self) {
+        self.release();
+        self.current = None;
+        self.position = 0.0;
+        self.phase = 0.0;
+        self.tick = None;
+    }
+
+    /// Returns true when no note is playing and the voice is silent.
+    #[inline(always)]
+    pub fn is_idle(&self) -> bool {
+        self.playing.is_none() && self.voice.is_idle()
+    }
+
+    /// Generate the next sample, advancing at the sequencer's rate.
+    #[inline(always)]
+    pub fn gen(&mut self, fc: Fc) -> Signal {
+        if !self.steps.is_empty() {
+            self.clock(self.position);
+            self.position += self.rate / self.sample_rate;
+        }
+        self.voice.gen(fc)
+    }
+
+    /// Generate the next sample, playing one step per `division` of the
+    /// `transport`.  Steps are locked to the play position (the first step
+    /// lands on the start, and every pattern length after), and the playing
+    /// note is released while the transport is stopped.
+    #[inline(always)]
+    pub fn sync(
+        &mut self,
+        fc: Fc,
+        transport: &Transport,
+        division: Division,
+    ) -> Signal {
+        if !transport.is_playing() {
+            self.release();
+        } else if !self.steps.is_empty() {
+            self.clock(transport.beats() / division.beats());
+        }
+        self.voice.gen(fc)
+    }
+
+    /// Move to a straight position in steps from the start, starting a new
+    /// step and ending the gate as needed.
+    #[inline(always)]
+    fn clock(&mut self, position: f64) {
+        let (tick, phase) = match self.groove.step(position) {
+            Some(step) => step,
+            // Waiting for a late first step
+            None => return,
+        };
+        if self.tick != Some(tick) {
+            self.tick = Some(tick);
+            self.advance((tick % self.steps.len() as u64) as usize);
+        }
+        self.phase = phase;
+        self.end_gate();
+    }
+
+    /// Release the note at the end of the current step's gate.
+    #[inline(always)]
+    fn end_gate(&mut self) {
+        if let Some(step) = self.current.map(|step| self.steps[step]) {
+            if !step.tie && self.phase >= step.gate {
+                self.release();
+            }
+        }
+    }
+
+    /// Release the playing note.
+    fn release(&mut self) {
+        if let Some(note) = self.playing.take() {
+            self.voice.note_off(note);
+        }
+    }
+
+    /// Start step number `next`.
+    fn advance(&mut self, next: usize) {
+        let tied = self.current.map_or(false, |step| self.steps[step].tie);
+        let step = self.steps[next];
+        let random = (f64::from(self.white.noise()) + 1.0) * 0.5;
+        let note = step
+            .note
+            .filter(|_| step.probability >= 1.0 || random < step.probability);
+        match (note, self.playing) {
+            (Some(note), Some(playing)) if tied => {
+                if note != playing {
+                    self.voice.legato(playing, note, step.velocity);
+                    self.playing = Some(note);
+                }
+            }
+            (Some(note), _) => {
+                self.release();
+                self.voice.note_on(note, step.velocity);
+                self.playing = Some(note);
+            }
+            (None, _) => self.release(),
+        }
+        self.current = Some(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Synth;
+    use fon::Stream;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Event {
+        On(Note),
+        Off(Note),
+        Legato(Note, Note),
+    }
+
+    /// Voice that records what it's told to play, and on which sample.
+    #[derive(Clone, Debug, Default)]
+    struct Recorder {
+        time: usize,
+        events: Vec<(usize, Event)>,
+    }
+
+    impl Voice for Recorder {
+        fn note_on(&mut self, note: Note, _velocity: Velocity) {
+            self.events.push((self.time, Event::On(note)));
+        }
+
+        fn note_off(&mut self, note: Note) {
+            self.events.push((self.time, Event::Off(note)));
+        }
+
+        fn legato(&mut self, from: Note, note: Note, _velocity: Velocity) {
+            self.events.push((self.time, Event::Legato(from, note)));
+        }
+
+        fn is_idle(&self) -> bool {
+            true
+        }
+
+        fn gen(&mut self, _fc: Fc) -> Signal {
+            self.time += 1;
+            0.0.into()
+        }
+    }
+
+    /// Play `samples` samples at 4 samples per step, returning the events.
+    fn play(seq: Sequencer<Recorder>, samples: usize) -> Vec<(usize, Event)> {
+        let mut synth = Synth::new(seq, |seq, fc| seq.gen(fc));
+        let mut stream = &mut synth;
+        stream.set_sample_rate(4.0);
+        Iterator::take(stream, samples).for_each(drop);
+        synth.params().voice().events.clone()
+    }
+
+    fn note(number: u8) -> Note {
+        Note::from_midi(number)
+    }
+
+    #[test]
+    fn gate_release() {
+        let mut seq = Sequencer::new(Recorder::default(), 4.0, 1.0);
+        seq.step(Step::new(note(60)));
+        seq.step(Step {
+            gate: 1.0,
+            ..Step::new(note(62))
+        });
+        assert_eq!(
+            play(seq, 12),
+            [
+                (0, Event::On(note(60))),
+                (2, Event::Off(note(60))),
+                (4, Event::On(note(62))),
+                // A full gate is held until the next step
+                (8, Event::Off(note(62))),
+                (8, Event::On(note(60))),
+                (10, Event::Off(note(60))),
+            ]
+        );
+    }
+
+    #[test]
+    fn tie_legato() {
+        let mut seq = Sequencer::new(Recorder::default(), 4.0, 1.0);
+        seq.step(Step {
+            tie: true,
+            ..Step::new(note(60))
+        });
+        seq.step(Step {
+            tie: true,
+            ..Step::new(note(62))
+        });
+        seq.step(Step::new(note(62)));
+        assert_eq!(
+            play(seq, 12),
+            [
+                (0, Event::On(note(60))),
+                (4, Event::Legato(note(60), note(62))),
+                // Tied into the same note, so it keeps sounding
+                (10, Event::Off(note(62))),
+            ]
+        );
+    }
+
+    #[test]
+    fn probability_skip() {
+        let mut seq = Sequencer::new(Recorder::default(), 4.0, 1.0);
+        seq.step(Step {
+            probability: 0.0,
+            ..Step::new(note(60))
+        });
+        seq.step(Step::new(note(62)));
+        assert_eq!(
+            play(seq, 8),
+            [(4, Event::On(note(62))), (6, Event::Off(note(62)))]
+        );
+
+        let mut seq = Sequencer::new(Recorder::default(), 4.0, 1.0);
+        seq.step(Step {
+            probability: 0.5,
+            ..Step::new(note(60))
+        });
+        let played = play(seq, 400)
+            .iter()
+            .filter(|(_, event)| *event == Event::On(note(60)))
+            .count();
+        assert!(played > 20 && played < 80, "{} of 100 played", played);
+    }
+
+    #[test]
+    fn groove_wrap() {
+        let mut seq = Sequencer::new(Recorder::default(), 4.0, 1.0);
+        seq.groove(Groove::swing(0.75));
+        seq.step(Step::new(note(60)));
+        seq.step(Step::new(note(62)));
+        assert_eq!(
+            play(seq, 12),
+            [
+                (0, Event::On(note(60))),
+                (3, Event::Off(note(60))),
+                // Second step is half a step late, and half as long
+                (6, Event::On(note(62))),
+                (7, Event::Off(note(62))),
+                // Back to the first step on time
+                (8, Event::On(note(60))),
+                (11, Event::Off(note(60))),
+            ]
+        );
+    }
+}
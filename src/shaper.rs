@@ -0,0 +1,73 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{sig::Signal, wavetable::Interp};
+
+/// Waveshaper.  Distorts a signal by mapping each sample through a transfer
+/// function stored as a lookup table.
+#[derive(Clone, Debug)]
+pub struct Waveshaper {
+    table: Vec<f64>,
+    interp: Interp,
+}
+
+impl Waveshaper {
+    /// Create a new waveshaper from a lookup table.  The first entry is the
+    /// output for an input of -1, and the last entry for an input of 1, with
+    /// the rest evenly spaced between.
+    pub fn new(interp: Interp, table: &[f64]) -> Self {
+        assert!(
+            table.len() >= 2,
+            "Waveshaper table needs at least 2 entries"
+        );
+        Self {
+            table: table.to_vec(),
+            interp,
+        }
+    }
+
+    /// Create a new waveshaper by sampling a transfer function at `size`
+    /// evenly spaced inputs from -1 to 1.
+    pub fn from_fn<F: Fn(f64) -> f64>(
+        interp: Interp,
+        size: usize,
+        transfer: F,
+    ) -> Self {
+        assert!(size >= 2, "Waveshaper table needs at least 2 entries");
+        let last = (size - 1) as f64;
+        let table: Vec<f64> = (0..size)
+            .map(|i| transfer(i as f64 / last * 2.0 - 1.0))
+            .collect();
+        Self { table, interp }
+    }
+
+    /// Shape the next sample of a signal (clamped to -1~1).
+    #[inline(always)]
+    pub fn shape(&self, input: Signal) -> Signal {
+        let last = self.table.len() - 1;
+        let pos = (f64::from(input).clamp(-1.0, 1.0) + 1.0) * 0.5 * last as f64;
+        let i = (pos as usize).min(last - 1);
+        let frac = pos - i as f64;
+        let at = |o: isize| {
+            self.table[(i as isize + o).clamp(0, last as isize) as usize]
+        };
+        match self.interp {
+            Interp::Linear => at(0) + (at(1) - at(0)) * frac,
+            Interp::Cubic => {
+                let (y0, y1, y2, y3) = (at(-1), at(0), at(1), at(2));
+                let a = -0.5 * y0 + 1.5 * y1 - 1.5 * y2 + 0.5 * y3;
+                let b = y0 - 2.5 * y1 + 2.0 * y2 - 0.5 * y3;
+                let c = -0.5 * y0 + 0.5 * y2;
+                ((a * frac + b) * frac + c) * frac + y1
+            }
+        }
+        .into()
+    }
+}
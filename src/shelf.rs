@@ -0,0 +1,68 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+use std::f64::consts::PI;
+
+/// Which frequencies a [`Shelf`] filter boosts or cuts.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ShelfMode {
+    /// Boost or cut frequencies below the corner frequency.
+    Low,
+    /// Boost or cut frequencies above the corner frequency.
+    High,
+}
+
+/// First-order (6 dB/octave) shelving filter.  For second-order shelves, use
+/// [`BiquadMode::LowShelf`](crate::BiquadMode::LowShelf) or
+/// [`BiquadMode::HighShelf`](crate::BiquadMode::HighShelf).
+#[derive(Copy, Clone, Debug)]
+pub struct Shelf {
+    mode: ShelfMode,
+    sample_rate: f64,
+    state: f64,
+}
+
+impl Shelf {
+    /// Create a new first-order shelving filter.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R, mode: ShelfMode) -> Self {
+        Self {
+            mode,
+            sample_rate: sample_rate.into(),
+            state: 0.0,
+        }
+    }
+
+    /// Filter the next sample of a signal.
+    ///
+    /// - `freq`: The corner frequency in Hz
+    /// - `gain`: The boost (positive) or cut (negative) in dB
+    #[inline(always)]
+    pub fn filter<C: Into<Signal>>(
+        &mut self,
+        input: Signal,
+        freq: C,
+        gain: f64,
+    ) -> Signal {
+        let x = f64::from(input);
+        let freq = f64::from(freq.into()).clamp(0.0, self.sample_rate * 0.49);
+        let g = (PI * freq / self.sample_rate).tan();
+        // One-pole lowpass (topology-preserving transform)
+        let v = (x - self.state) * g / (1.0 + g);
+        let lowpass = v + self.state;
+        self.state = lowpass + v;
+        let band = match self.mode {
+            ShelfMode::Low => lowpass,
+            ShelfMode::High => x - lowpass,
+        };
+        (x + (10.0f64.powf(gain / 20.0) - 1.0) * band).into()
+    }
+}
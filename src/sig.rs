@@ -10,8 +10,9 @@
 
 //! Digital audio signal.
 
+use crate::{chip::Duty, fold::Fold};
 use fon::{chan::Ch64, mono::Mono};
-use std::f64::consts::PI;
+use std::f64::consts::{FRAC_2_PI, PI};
 
 /// A signed digital audio signal that can be routed through processing
 /// components.  This differs from `Mono64` in that the values are not clamped
@@ -40,6 +41,60 @@ impl Signal {
         Self((self.0 - phase_shifted.0).signum())
     }
 
+    /// Phase distortion (CZ-style) generator component - takes a sawtooth
+    /// (`Fc`) wave, warping it with a piecewise-linear curve before reading a
+    /// cosine.
+    /// - `amount`: Distortion amount - range: 0~1 (0.0 for sine wave)
+    #[inline(always)]
+    pub fn phase_distort<S: Into<Self>>(self, amount: S) -> Self {
+        let knee = -amount.into().0.clamp(0.0, 0.999);
+        let phase = if self.0 < knee {
+            (self.0 + 1.0) / (knee + 1.0) - 1.0
+        } else {
+            (self.0 - knee) / (1.0 - knee)
+        };
+        Self((phase * PI).cos())
+    }
+
+    /// Chiptune (NES / Game Boy style) 8-step pulse wave generator component -
+    /// takes a sawtooth (`Fc`) wave.
+    #[inline(always)]
+    pub fn pulse_chip(self, duty: Duty) -> Self {
+        let step = (((self.0 + 1.0) * 4.0) as u8).min(7);
+        if duty.sequence() & (0x80 >> step) != 0 {
+            Self(1.0)
+        } else {
+            Self(-1.0)
+        }
+    }
+
+    /// Chiptune (NES style) 4-bit, 32-step triangle wave generator component
+    /// - takes a sawtooth (`Fc`) wave.
+    #[inline(always)]
+    pub fn triangle_chip(self) -> Self {
+        let step = (((self.0 + 1.0) * 16.0) as u8).min(31);
+        let level = if step < 16 { 15 - step } else { step - 16 };
+        Self(f64::from(level) / 7.5 - 1.0)
+    }
+
+    /// Glottal pulse (Rosenberg) generator component - takes a sawtooth (`Fc`)
+    /// wave.  Outputs the airflow through the vocal folds (0 to 1), for
+    /// exciting a [`Tract`](crate::Tract).
+    /// - `open`: Fraction of each cycle the vocal folds are open - range: 0~1
+    #[inline(always)]
+    pub fn glottal<S: Into<Self>>(self, open: S) -> Self {
+        let open = open.into().0.clamp(0.01, 1.0);
+        let t = (self.0 + 1.0) * 0.5;
+        let (rise, fall) = (open * 2.0 / 3.0, open / 3.0);
+        if t < rise {
+            Self(0.5 - 0.5 * (PI * t / rise).cos())
+        } else if t < open {
+            Self((PI * 0.5 * (t - rise) / fall).cos())
+        } else {
+            Self(0.0)
+        }
+    }
+
     /// Shift signal.  Takes a signal and adds an amount to it, wrapping to -1
     /// if it goes over 1, and to 1 if it goes under -1.
     #[inline(always)]
@@ -57,6 +112,14 @@ impl Signal {
         Self(self.0 * volume.into().0)
     }
 
+    /// Ring modulation.  Multiplies the signal by a bipolar (-1 to 1) carrier
+    /// signal, replacing the frequencies of both with their sums and
+    /// differences.  Output stays within -1 to 1 when both inputs do.
+    #[inline(always)]
+    pub fn ring_mod<S: Into<Self>>(self, carrier: S) -> Self {
+        Self(self.0 * carrier.into().0)
+    }
+
     /// Invert (negate) signal.
     #[inline(always)]
     pub fn invert(self) -> Self {
@@ -106,6 +169,99 @@ impl Signal {
         )
     }
 
+    /// Tube style saturation.  Asymmetric soft clipping (the negative half
+    /// compresses more gradually than the positive half), adding both even
+    /// and odd harmonics.
+    ///
+    /// - `drive`: Gain before saturating - range: 0+
+    /// - `bias`: Operating point offset (more asymmetry, the resulting DC
+    ///   offset is removed) - range: -1~1
+    /// - `trim`: Output gain in dB
+    #[inline(always)]
+    pub fn saturate_tube<D, B, T>(self, drive: D, bias: B, trim: T) -> Self
+    where
+        D: Into<Self>,
+        B: Into<Self>,
+        T: Into<Self>,
+    {
+        fn curve(u: f64) -> f64 {
+            if u >= 0.0 {
+                u.tanh()
+            } else {
+                u / (1.0 - u)
+            }
+        }
+        let (bias, trim) = (bias.into().0, trim.into().0);
+        let u = self.0 * drive.into().0 + bias;
+        Self((curve(u) - curve(bias)) * 10.0f64.powf(trim / 20.0))
+    }
+
+    /// Tape style saturation.  Gentle, mostly symmetric soft clipping that
+    /// squashes peaks, adding mostly odd harmonics.
+    ///
+    /// - `drive`: Gain before saturating - range: 0+
+    /// - `bias`: Tape bias offset (adds even harmonics, the resulting DC
+    ///   offset is removed) - range: -1~1
+    /// - `trim`: Output gain in dB
+    #[inline(always)]
+    pub fn saturate_tape<D, B, T>(self, drive: D, bias: B, trim: T) -> Self
+    where
+        D: Into<Self>,
+        B: Into<Self>,
+        T: Into<Self>,
+    {
+        fn curve(u: f64) -> f64 {
+            u.atan() * FRAC_2_PI
+        }
+        let (bias, trim) = (bias.into().0, trim.into().0);
+        let u = self.0 * drive.into().0 + bias;
+        Self((curve(u) - curve(bias)) * 10.0f64.powf(trim / 20.0))
+    }
+
+    /// Waveshape with a weighted sum of Chebyshev polynomials (input clamped
+    /// to -1~1).  When the input is a full scale sine wave, `harmonics[n]` is
+    /// the exact amplitude of harmonic `n + 1` in the output.
+    #[inline(always)]
+    pub fn chebyshev(self, harmonics: &[f64]) -> Self {
+        let x = self.0.clamp(-1.0, 1.0);
+        // Recurrence: T(n + 1) = 2x T(n) - T(n - 1)
+        let (mut prev, mut curr) = (1.0, x);
+        let mut out = 0.0;
+        for weight in harmonics {
+            out += weight * curr;
+            let next = 2.0 * x * curr - prev;
+            prev = curr;
+            curr = next;
+        }
+        Self(out)
+    }
+
+    /// West coast style wavefolder.  Instead of clipping, parts of the signal
+    /// amplified past -1 or 1 are folded back over, adding harmonics.
+    ///
+    /// - `gain`: Amplification before folding - range: 1+ (more folds)
+    /// - `symmetry`: Offset added before folding (adds even harmonics, and DC
+    ///   offset) - range: -1~1
+    #[inline(always)]
+    pub fn fold<G, S>(self, fold: Fold, gain: G, symmetry: S) -> Self
+    where
+        G: Into<Self>,
+        S: Into<Self>,
+    {
+        let x = self.0 * gain.into().0 + symmetry.into().0;
+        Self(match fold {
+            Fold::Sine => (x * PI * 0.5).sin(),
+            Fold::Triangle => {
+                let t = (x + 1.0).rem_euclid(4.0);
+                if t < 2.0 {
+                    t - 1.0
+                } else {
+                    3.0 - t
+                }
+            }
+        })
+    }
+
     /// Clamp a signal -1 to 1 (hard clipping)
     #[inline(always)]
     pub fn clamp(self) -> Self {
@@ -114,7 +270,10 @@ impl Signal {
 
     /// Convert signal into Mono channel.
     #[inline(always)]
+    #[allow(clippy::manual_clamp)]
     pub fn to_mono(self) -> Mono<Ch64> {
+        // Clipped to -1~1, with NaN forced to 1.0 (`f64::min()` returns the
+        // non-NaN operand)
         Mono::new(Ch64::new(self.0.min(1.0).max(-1.0)))
     }
 }
@@ -0,0 +1,321 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    midi::{Midi, MidiMessage},
+    poly::Voice,
+    synth::Synth,
+};
+use fon::{mono::Mono64, Audio, Sink};
+use std::fmt::Debug;
+
+/// Event in a track, at a time in ticks
+#[derive(Copy, Clone, Debug)]
+enum Event {
+    // Microseconds per quarter note
+    Tempo(u32),
+    Message(MidiMessage),
+}
+
+/// Reads from the bytes of a chunk
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.0.len() {
+            return None;
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let bytes = self.bytes(2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.bytes(4)?;
+        Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Variable length quantity
+    fn vlq(&mut self) -> Option<u32> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = value << 7 | u32::from(byte & 0x7F);
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// Chunk with a four letter type
+    fn chunk(&mut self, kind: &[u8; 4]) -> Option<Reader<'a>> {
+        loop {
+            let found = self.bytes(4)?;
+            let len = self.u32()?;
+            let data = self.bytes(len as usize)?;
+            // Unknown chunks are skipped
+            if found == kind {
+                return Some(Reader(data));
+            }
+        }
+    }
+}
+
+/// Standard MIDI file (type 0 or 1) player.  Schedules the note and
+/// controller events of all tracks, and plays them through a [`Midi`] input
+/// on a [`Synth`] to render the whole file.
+#[derive(Clone, Debug)]
+pub struct MidiFile {
+    // Time in seconds, and message
+    events: Vec<(f64, MidiMessage)>,
+    // Time of the end of the last track in seconds
+    length: f64,
+}
+
+impl MidiFile {
+    /// Load a standard MIDI file from its bytes.  Returns `None` if the file
+    /// is invalid, or a type 2 file (independent sequences).
+    pub fn new(bytes: &[u8]) -> Option<Self> {
+        let mut file = Reader(bytes);
+        let mut header = file.chunk(b"MThd")?;
+        let format = header.u16()?;
+        let tracks = header.u16()?;
+        let division = header.u16()?;
+        if format > 1 {
+            return None;
+        }
+        // Length of a tick: ticks per quarter note, or SMPTE frames per
+        // second and ticks per frame
+        let ticks = f64::from(division & 0x7FFF);
+        let smpte = if division & 0x8000 == 0 {
+            None
+        } else {
+            let ticks = f64::from(division & 0xFF);
+            let fps = match ((division >> 8) as u8 as i8).unsigned_abs() {
+                24 => 24.0,
+                25 => 25.0,
+                29 => 29.97,
+                30 => 30.0,
+                _ => return None,
+            };
+            Some(fps * ticks)
+        };
+        if smpte == Some(0.0) || ticks == 0.0 {
+            return None;
+        }
+        let seconds_per_tick = |tempo: u32| match smpte {
+            Some(ticks_per_second) => ticks_per_second.recip(),
+            None => f64::from(tempo) / 1_000_000.0 / ticks,
+        };
+        // Merge tracks, ordered by time in ticks
+        let mut timeline = Vec::new();
+        let mut end = 0;
+        for _ in 0..tracks {
+            end = end.max(Self::track(file.chunk(b"MTrk")?, &mut timeline)?);
+        }
+        timeline.sort_by_key(|(tick, _)| *tick);
+        let (mut tick, mut time) = (0, 0.0);
+        let mut tick_len = seconds_per_tick(500_000);
+        let mut events = Vec::new();
+        for (at, event) in timeline {
+            time += f64::from(at - tick) * tick_len;
+            tick = at;
+            match event {
+                Event::Tempo(tempo) => tick_len = seconds_per_tick(tempo),
+                Event::Message(message) => events.push((time, message)),
+            }
+        }
+        let length = time + f64::from(end.max(tick) - tick) * tick_len;
+        Some(Self { events, length })
+    }
+
+    /// Read the events of a track, returning the time of the end of track.
+    fn track(
+        mut track: Reader<'_>,
+        timeline: &mut Vec<(u32, Event)>,
+    ) -> Option<u32> {
+        let (mut tick, mut running) = (0u32, 0u8);
+        while !track.0.is_empty() {
+            tick = tick.checked_add(track.vlq()?)?;
+            let (status, first) = match track.u8()? {
+                // Running status: this byte is the first data byte
+                data if data < 0x80 => (running, Some(data)),
+                status => (status, None),
+            };
+            match status {
+                0xFF => {
+                    let kind = track.u8()?;
+                    let len = track.vlq()? as usize;
+                    let data = track.bytes(len)?;
+                    match kind {
+                        0x2F => break,
+                        0x51 if len == 3 => {
+                            let tempo = u32::from_be_bytes([
+                                0, data[0], data[1], data[2],
+                            ]);
+                            timeline.push((tick, Event::Tempo(tempo)));
+                        }
+                        _ => {}
+                    }
+                }
+                0xF0 | 0xF7 => {
+                    running = 0;
+                    let len = track.vlq()? as usize;
+                    track.bytes(len)?;
+                }
+                0x80..=0xEF => {
+                    running = status;
+                    let mut bytes = [status, 0, 0];
+                    bytes[1] = match first {
+                        Some(data) => data,
+                        None => track.u8()?,
+                    };
+                    let len = match status & 0xF0 {
+                        0xC0 | 0xD0 => 2,
+                        _ => {
+                            bytes[2] = track.u8()?;
+                            3
+                        }
+                    };
+                    if let Some(message) = MidiMessage::parse(&bytes[..len]) {
+                        timeline.push((tick, Event::Message(message)));
+                    }
+                }
+                _ => return None,
+            }
+        }
+        Some(tick)
+    }
+
+    /// Get the events, with their times in seconds.
+    #[inline(always)]
+    pub fn events(&self) -> &[(f64, MidiMessage)] {
+        &self.events
+    }
+
+    /// Get the length of the file in seconds.
+    #[inline(always)]
+    pub fn len(&self) -> f64 {
+        self.length
+    }
+
+    /// Returns true if the file has no length.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.length <= 0.0
+    }
+
+    /// Render the whole file, playing the events through `midi` on `synth`.
+    ///
+    /// - `tail`: Extra time in seconds to render after the end of the file
+    ///   (for releases and reverb tails)
+    pub fn render<T: Debug + Voice, R: Into<f64>>(
+        &self,
+        synth: &mut Synth<T>,
+        midi: &mut Midi,
+        sample_rate: R,
+        tail: f64,
+    ) -> Audio<Mono64> {
+        let sample_rate = sample_rate.into();
+        let len = ((self.length + tail.max(0.0)) * sample_rate).ceil();
+        let mut audio =
+            Audio::<Mono64>::with_silence(sample_rate, len as usize);
+        let mut start = 0;
+        for (time, message) in self.events.iter() {
+            let end = ((time * sample_rate) as usize).min(audio.len());
+            if end > start {
+                audio.sink(start..end).stream(&mut *synth);
+                start = end;
+            }
+            midi.message(*message, synth.params());
+        }
+        if audio.len() > start {
+            audio.sink(start..).stream(&mut *synth);
+        }
+        audio
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a file with one track from its header division and track data.
+    fn file(division: u16, track: &[u8]) -> Vec<u8> {
+        let mut bytes = b"MThd\0\0\0\x06\0\0\0\x01".to_vec();
+        bytes.extend_from_slice(&division.to_be_bytes());
+        bytes.extend_from_slice(b"MTrk");
+        bytes.extend_from_slice(&(track.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(track);
+        bytes
+    }
+
+    /// Note on, note off a quarter note later, end of track.
+    const TRACK: &[u8] = &[
+        0x00, 0x90, 60, 100, 0x60, 0x80, 60, 64, 0x00, 0xFF, 0x2F, 0x00,
+    ];
+
+    #[test]
+    fn valid() {
+        let midi = MidiFile::new(&file(96, TRACK)).unwrap();
+        assert_eq!(midi.events().len(), 2);
+        assert_eq!(midi.events()[1].0, 0.5);
+        assert_eq!(midi.len(), 0.5);
+        // 25 frames per second, 40 ticks per frame (1 millisecond per tick)
+        let midi = MidiFile::new(&file(0xE728, TRACK)).unwrap();
+        assert!((midi.len() - 0.096).abs() < 1e-12);
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = file(96, TRACK);
+        for len in 0..bytes.len() {
+            assert!(MidiFile::new(&bytes[..len]).is_none(), "length {}", len);
+        }
+    }
+
+    #[test]
+    fn bad_division() {
+        // Zero ticks per quarter note
+        assert!(MidiFile::new(&file(0, TRACK)).is_none());
+        // SMPTE with -128 frames per second
+        assert!(MidiFile::new(&file(0x8000, TRACK)).is_none());
+        // SMPTE with an unsupported frame rate
+        assert!(MidiFile::new(&file(0xE628, TRACK)).is_none());
+        // SMPTE with zero ticks per frame
+        assert!(MidiFile::new(&file(0xE800, TRACK)).is_none());
+    }
+
+    #[test]
+    fn bad_track() {
+        // Running status without a previous status
+        assert!(MidiFile::new(&file(96, &[0x00, 60, 100])).is_none());
+        // Variable length quantity longer than 4 bytes
+        let vlq = [0xFF, 0xFF, 0xFF, 0xFF, 0x7F, 0x90, 60, 100];
+        assert!(MidiFile::new(&file(96, &vlq)).is_none());
+        // Meta event longer than the track
+        let meta = [0x00, 0xFF, 0x51, 0x7F, 0x07];
+        assert!(MidiFile::new(&file(96, &meta)).is_none());
+        // Type 2 file
+        let mut bytes = file(96, TRACK);
+        bytes[9] = 2;
+        assert!(MidiFile::new(&bytes).is_none());
+    }
+}
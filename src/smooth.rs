@@ -0,0 +1,55 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+
+/// One-pole lowpass smoothing filter.  Useful as a tone control, and for
+/// smoothing control signals.
+#[derive(Copy, Clone, Debug)]
+pub struct Smooth {
+    sample_rate: f64,
+    coeff: f64,
+    out: f64,
+}
+
+impl Smooth {
+    /// Create a new one-pole smoothing filter.
+    ///
+    /// - `time`: Time constant in seconds (time to reach about 63% of a step
+    ///   change)
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R, time: f64) -> Self {
+        let mut smooth = Self {
+            sample_rate: sample_rate.into(),
+            coeff: 0.0,
+            out: 0.0,
+        };
+        smooth.time(time);
+        smooth
+    }
+
+    /// Change the time constant in seconds.
+    #[inline(always)]
+    pub fn time(&mut self, time: f64) {
+        self.coeff = if time > 0.0 {
+            (-1.0 / (time * self.sample_rate)).exp()
+        } else {
+            0.0
+        };
+    }
+
+    /// Filter the next sample of a signal.
+    #[inline(always)]
+    pub fn filter(&mut self, input: Signal) -> Signal {
+        let x = f64::from(input);
+        self.out = x + self.coeff * (self.out - x);
+        self.out.into()
+    }
+}
@@ -0,0 +1,108 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::curve::curve;
+
+/// Smoothed parameter.  Ramps toward a new value whenever it's changed, so
+/// that changing a frequency or gain mid-render doesn't click.  Works with
+/// `f64` and [`Signal`](crate::Signal).
+#[derive(Copy, Clone, Debug)]
+pub struct Smoothed<T> {
+    sample_rate: f64,
+    // Ramp time in seconds
+    time: f64,
+    curvature: f64,
+    value: f64,
+    target: T,
+    // Value at the start of the ramp
+    start: f64,
+    // Progress through the ramp (0 to 1), and how much to advance it each
+    // sample
+    t: f64,
+    dt: f64,
+}
+
+impl<T: Copy + From<f64> + Into<f64>> Smoothed<T> {
+    /// Create a new smoothed parameter starting at `value`.
+    ///
+    /// - `time`: Time in seconds to ramp to each new value
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R, time: f64, value: T) -> Self {
+        Self {
+            sample_rate: sample_rate.into(),
+            time,
+            curvature: 0.0,
+            value: value.into(),
+            target: value,
+            start: value.into(),
+            t: 1.0,
+            dt: 0.0,
+        }
+    }
+
+    /// Change the ramp time in seconds (takes effect at the next change).
+    #[inline(always)]
+    pub fn time(&mut self, time: f64) {
+        self.time = time;
+    }
+
+    /// Change the curvature of the ramp (linear by default).  0 is linear,
+    /// positive values start slow and end fast, and negative values start
+    /// fast and end slow.
+    #[inline(always)]
+    pub fn curvature(&mut self, curvature: f64) {
+        self.curvature = curvature;
+    }
+
+    /// Start ramping toward a new value.
+    #[inline(always)]
+    pub fn set(&mut self, target: T) {
+        self.target = target;
+        self.start = self.value;
+        self.t = 0.0;
+        self.dt = (self.time * self.sample_rate).round().max(1.0).recip();
+    }
+
+    /// Jump to a new value immediately.
+    #[inline(always)]
+    pub fn reset(&mut self, value: T) {
+        self.value = value.into();
+        self.target = value;
+        self.t = 1.0;
+    }
+
+    /// Get the value being ramped toward.
+    #[inline(always)]
+    pub fn target(&self) -> T {
+        self.target
+    }
+
+    /// Returns true while ramping.
+    #[inline(always)]
+    pub fn is_smoothing(&self) -> bool {
+        self.t < 1.0
+    }
+
+    /// Generate the next value of the parameter.
+    #[inline(always)]
+    pub fn gen(&mut self) -> T {
+        if self.t >= 1.0 {
+            return self.target;
+        }
+        self.t += self.dt;
+        let end = self.target.into();
+        self.value = if self.t >= 1.0 {
+            end
+        } else {
+            self.start + (end - self.start) * curve(self.t, self.curvature)
+        };
+        self.value.into()
+    }
+}
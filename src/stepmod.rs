@@ -0,0 +1,110 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{sig::Signal, smoothed::Smoothed};
+
+/// Modulation step sequencer.  Outputs the value of each step in turn,
+/// optionally gliding into it, clocked by a rate or by a trigger signal.
+#[derive(Clone, Debug)]
+pub struct StepMod {
+    sample_rate: f64,
+    // Value and glide of each step
+    steps: Vec<(f64, bool)>,
+    // Index of the current step (`None` before the first step)
+    current: Option<usize>,
+    // Progress through the current step when clocked by rate (0 to 1)
+    phase: f64,
+    // Last trigger signal value
+    trigger: f64,
+    value: Smoothed<f64>,
+}
+
+impl StepMod {
+    /// Create a new step sequencer with no steps.
+    ///
+    /// - `glide`: Time in seconds to glide into steps that have glide on
+    pub fn new<R: Into<f64>>(sample_rate: R, glide: f64) -> Self {
+        let sample_rate = sample_rate.into();
+        Self {
+            sample_rate,
+            steps: Vec::new(),
+            current: None,
+            phase: 0.0,
+            trigger: f64::INFINITY,
+            value: Smoothed::new(sample_rate, glide, 0.0),
+        }
+    }
+
+    /// Add a step to the end of the sequence, returning the index of the
+    /// step.
+    ///
+    /// - `glide`: If true, glide from the previous step's value into this one
+    pub fn step(&mut self, value: f64, glide: bool) -> usize {
+        self.steps.push((value, glide));
+        self.steps.len() - 1
+    }
+
+    /// Change step number `step`.
+    #[inline(always)]
+    pub fn set(&mut self, step: usize, value: f64, glide: bool) {
+        self.steps[step] = (value, glide);
+    }
+
+    /// Change the glide time in seconds.
+    #[inline(always)]
+    pub fn glide(&mut self, glide: f64) {
+        self.value.time(glide);
+    }
+
+    /// Go back to before the first step.
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.current = None;
+        self.phase = 0.0;
+    }
+
+    /// Generate the next sample, advancing `rate` steps per second (see
+    /// [`Division::hz()`](crate::Division::hz) for tempo sync).
+    #[inline(always)]
+    pub fn gen(&mut self, rate: f64) -> Signal {
+        let phase = self.phase + rate / self.sample_rate;
+        if self.current.is_none() || phase >= 1.0 {
+            self.advance();
+        }
+        self.phase = phase.fract();
+        self.value.gen().into()
+    }
+
+    /// Generate the next sample, advancing one step each time `trigger`
+    /// rises above 0.
+    #[inline(always)]
+    pub fn trigger(&mut self, trigger: Signal) -> Signal {
+        let trigger = f64::from(trigger);
+        if self.trigger <= 0.0 && trigger > 0.0 {
+            self.advance();
+        }
+        self.trigger = trigger;
+        self.value.gen().into()
+    }
+
+    fn advance(&mut self) {
+        if self.steps.is_empty() {
+            return;
+        }
+        let next = self.current.map_or(0, |step| (step + 1) % self.steps.len());
+        let (value, glide) = self.steps[next];
+        if glide {
+            self.value.set(value);
+        } else {
+            self.value.reset(value);
+        }
+        self.current = Some(next);
+    }
+}
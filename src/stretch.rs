@@ -0,0 +1,153 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::fft::{Complex, Fft};
+use fon::{mono::Mono64, Audio, Frame};
+use std::f64::consts::TAU;
+
+/// Phase vocoder, for offline time stretching (or compressing) of audio
+/// without changing it's pitch.  Uses identity phase locking around spectral
+/// peaks to reduce the "phasey" smearing of a basic phase vocoder.
+#[derive(Clone, Debug)]
+pub struct PhaseVocoder {
+    fft: Fft,
+    window: Vec<f64>,
+}
+
+impl PhaseVocoder {
+    /// Create a new phase vocoder.
+    ///
+    /// - `size`: FFT size in samples (a power of two) - larger has better
+    ///   frequency resolution, but smears transients, for example 2048
+    pub fn new(size: usize) -> Self {
+        assert!(size >= 4, "Phase vocoder FFT size must be at least 4");
+        let window = (0..size)
+            .map(|i| 0.5 - 0.5 * (TAU * i as f64 / size as f64).cos())
+            .collect();
+        Self {
+            fft: Fft::new(size),
+            window,
+        }
+    }
+
+    /// Stretch `audio` to `factor` times it's length (2.0 for half speed,
+    /// 0.5 for double speed).
+    pub fn stretch(&self, audio: &Audio<Mono64>, factor: f64) -> Audio<Mono64> {
+        assert!(factor > 0.0, "Stretch factor must be positive");
+        let input: Vec<f64> =
+            audio.iter().map(|f| f64::from(f.channels()[0])).collect();
+        let n = self.fft.size();
+        let bins = n / 2 + 1;
+        let synthesis_hop = n / 4;
+        let out_len = (input.len() as f64 * factor).round() as usize;
+        let frames = out_len / synthesis_hop + 2;
+        let mut output = vec![0.0; frames * synthesis_hop + n];
+        let mut norm = vec![0.0; output.len()];
+        let mut spectrum = vec![Complex::default(); n];
+        let mut magnitude = vec![0.0; bins];
+        let mut phase = vec![0.0; bins];
+        let mut last_phase = vec![0.0; bins];
+        let mut synth_phase = vec![0.0; bins];
+        let mut peaks = Vec::new();
+        let mut last_center = 0;
+
+        for frame in 0..frames {
+            // Analysis
+            let center =
+                (frame as f64 * synthesis_hop as f64 / factor).round() as isize;
+            let start = center - (n / 2) as isize;
+            for (i, bin) in spectrum.iter_mut().enumerate() {
+                let j = start + i as isize;
+                let x = if j >= 0 {
+                    input.get(j as usize).cloned().unwrap_or(0.0)
+                } else {
+                    0.0
+                };
+                *bin = Complex::new(x * self.window[i], 0.0);
+            }
+            self.fft.forward(&mut spectrum);
+            for k in 0..bins {
+                magnitude[k] = spectrum[k].norm();
+                phase[k] = spectrum[k].arg();
+            }
+
+            // Phase propagation
+            if frame == 0 {
+                synth_phase.copy_from_slice(&phase);
+            } else {
+                let analysis_hop = (center - last_center) as f64;
+                peaks.clear();
+                for k in 0..bins {
+                    let neighbors = k.saturating_sub(2)..(k + 3).min(bins);
+                    if neighbors
+                        .filter(|&j| j != k)
+                        .all(|j| magnitude[k] > magnitude[j])
+                    {
+                        peaks.push(k);
+                    }
+                }
+                // Advance the phase of each peak by it's instantaneous
+                // frequency
+                for &k in peaks.iter() {
+                    let omega = TAU * k as f64 / n as f64;
+                    let freq = if analysis_hop > 0.0 {
+                        let delta =
+                            phase[k] - last_phase[k] - omega * analysis_hop;
+                        let delta = delta - TAU * (delta / TAU).round();
+                        omega + delta / analysis_hop
+                    } else {
+                        omega
+                    };
+                    synth_phase[k] = (synth_phase[k]
+                        + freq * synthesis_hop as f64)
+                        .rem_euclid(TAU);
+                }
+                // Lock the phase of other bins to their nearest peak
+                let mut p = 0;
+                for k in 0..bins {
+                    if peaks.is_empty() {
+                        break;
+                    }
+                    while p + 1 < peaks.len() && peaks[p + 1] + peaks[p] < 2 * k
+                    {
+                        p += 1;
+                    }
+                    let peak = peaks[p];
+                    if k != peak {
+                        synth_phase[k] =
+                            synth_phase[peak] + phase[k] - phase[peak];
+                    }
+                }
+            }
+            last_phase.copy_from_slice(&phase);
+            last_center = center;
+
+            // Synthesis
+            for k in 0..bins {
+                spectrum[k] = Complex::polar(magnitude[k], synth_phase[k]);
+                if k > 0 && k < n / 2 {
+                    spectrum[n - k] =
+                        Complex::new(spectrum[k].re, -spectrum[k].im);
+                }
+            }
+            self.fft.inverse(&mut spectrum);
+            let offset = frame * synthesis_hop;
+            for (i, bin) in spectrum.iter().enumerate() {
+                output[offset + i] += bin.re * self.window[i];
+                norm[offset + i] += self.window[i] * self.window[i];
+            }
+        }
+
+        let samples: Vec<f64> = (0..out_len)
+            .map(|i| output[i + n / 2] / norm[i + n / 2].max(1e-6))
+            .collect();
+        Audio::with_f64_buffer(audio.sample_rate(), samples)
+    }
+}
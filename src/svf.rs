@@ -0,0 +1,76 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+use std::f64::consts::PI;
+
+/// All of the outputs of a [`Svf`] for one sample.
+#[derive(Copy, Clone, Debug)]
+pub struct SvfOutput {
+    /// Lowpass output
+    pub lowpass: Signal,
+    /// Highpass output
+    pub highpass: Signal,
+    /// Bandpass output
+    pub bandpass: Signal,
+    /// Notch output
+    pub notch: Signal,
+}
+
+/// State-variable filter (topology-preserving transform / zero-delay
+/// feedback), which stays stable when the cutoff is modulated quickly (even at
+/// audio rate, for filter FM).
+#[derive(Copy, Clone, Debug)]
+pub struct Svf {
+    sample_rate: f64,
+    s1: f64,
+    s2: f64,
+}
+
+impl Svf {
+    /// Create a new state-variable filter.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        Self {
+            sample_rate: sample_rate.into(),
+            s1: 0.0,
+            s2: 0.0,
+        }
+    }
+
+    /// Filter the next sample of a signal, returning all outputs.
+    ///
+    /// - `cutoff`: The cutoff (or center) frequency in Hz
+    /// - `q`: The quality factor (0.707 for no resonance)
+    #[inline(always)]
+    pub fn filter<C: Into<Signal>>(
+        &mut self,
+        input: Signal,
+        cutoff: C,
+        q: f64,
+    ) -> SvfOutput {
+        let x = f64::from(input);
+        let cutoff =
+            f64::from(cutoff.into()).clamp(0.0, self.sample_rate * 0.49);
+        let g = (PI * cutoff / self.sample_rate).tan();
+        let k = 1.0 / q.max(0.01);
+        let hp = (x - (k + g) * self.s1 - self.s2) / (1.0 + g * (k + g));
+        let bp = g * hp + self.s1;
+        self.s1 = g * hp + bp;
+        let lp = g * bp + self.s2;
+        self.s2 = g * bp + lp;
+        SvfOutput {
+            lowpass: lp.into(),
+            highpass: hp.into(),
+            bandpass: bp.into(),
+            notch: (x - k * bp).into(),
+        }
+    }
+}
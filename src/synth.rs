@@ -8,13 +8,13 @@
 // At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
 // LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
 
-use crate::sig::Signal;
+use crate::{note::Note, poly::Voice, sig::Signal, velocity::Velocity};
 use fon::{mono::Mono64, Stream};
-use std::{borrow::Borrow, fmt::Debug, time::Duration};
+use std::{borrow::Borrow, f64::consts::TAU, fmt::Debug, time::Duration};
 
 /// Frequency counter.
 #[derive(Copy, Clone, Debug)]
-pub struct Fc(Duration);
+pub struct Fc(Duration, Duration);
 
 impl Fc {
     /// Sample frequency counter with a frequency.
@@ -25,6 +25,115 @@ impl Fc {
         // Return signal between -1 and 1
         (((nano % modu) << 1) as f64 / modu as f64 - 1.0).into()
     }
+
+    /// Band-limited sawtooth wave with a frequency.  Same as
+    /// [`freq()`](Fc::freq), but with PolyBLEP correction applied to the
+    /// discontinuity to reduce aliasing.
+    #[inline(always)]
+    pub fn saw_bl(&self, freq: f64) -> Signal {
+        let (t, dt) = self.phase(freq);
+        (2.0 * t - 1.0 - poly_blep(t, dt)).into()
+    }
+
+    /// Band-limited pulse wave with a frequency.  Same as
+    /// [`Signal::pulse()`](Signal::pulse) on [`freq()`](Fc::freq), but with
+    /// PolyBLEP correction applied to both edges to reduce aliasing.
+    /// - `half_duty`: ½ Duty cycle - range: 0~1 (1.0 for square wave)
+    #[inline(always)]
+    pub fn pulse_bl<S: Into<Signal>>(&self, freq: f64, half_duty: S) -> Signal {
+        let (t, dt) = self.phase(freq);
+        let duty = f64::from(half_duty.into()) * 0.5;
+        let naive = if t < 1.0 - duty { -1.0 } else { 1.0 };
+        let rise = poly_blep((t + duty) % 1.0, dt);
+        (naive - poly_blep(t, dt) + rise).into()
+    }
+
+    /// Band-limited harmonic series (DSF - Discrete Summation Formula) with a
+    /// frequency.  Each harmonic is quieter than the last by a factor of
+    /// `brightness`, and harmonics above the nyquist frequency are left out.
+    /// - `brightness`: Harmonic rolloff - range: 0~1 (0.0 for sine wave)
+    #[inline(always)]
+    pub fn dsf<S: Into<Signal>>(&self, freq: f64, brightness: S) -> Signal {
+        let (t, dt) = self.phase(freq);
+        let a = f64::from(brightness.into()).clamp(0.0, 0.999);
+        let theta = TAU * t;
+        let mut num = theta.sin();
+        // Remove harmonics above nyquist (if the sample rate is known).
+        if dt > 0.0 {
+            let n = (0.5 / dt).floor();
+            num -=
+                a.powf(n) * ((theta * (n + 1.0)).sin() - a * (theta * n).sin());
+        }
+        let den = 1.0 + a * a - 2.0 * a * theta.cos();
+        (num / den * (1.0 - a * a)).into()
+    }
+
+    /// Hard sync: sawtooth wave with frequency `freq` that restarts its cycle
+    /// every time the `master` frequency completes a cycle.
+    #[inline(always)]
+    pub fn sync(&self, master: f64, freq: f64) -> Signal {
+        let (t, _) = self.phase(master);
+        ((t * freq / master).fract() * 2.0 - 1.0).into()
+    }
+
+    /// Soft sync: sawtooth wave with frequency `freq` that reverses direction
+    /// every time the `master` frequency completes a cycle.
+    #[inline(always)]
+    pub fn sync_soft(&self, master: f64, freq: f64) -> Signal {
+        let (t, _) = self.phase(master);
+        let modu = Duration::new(1, 0).div_f64(master).as_nanos();
+        let ratio = freq / master;
+        let phase = if (self.0.as_nanos() / modu) & 1 == 0 {
+            t * ratio
+        } else {
+            ratio - t * ratio
+        };
+        (phase.rem_euclid(1.0) * 2.0 - 1.0).into()
+    }
+
+    /// Sub-oscillator: sawtooth wave `octaves` below frequency `freq`, phase
+    /// locked to [`freq()`](Fc::freq) so that they never drift apart.
+    #[inline(always)]
+    pub fn sub(&self, freq: f64, octaves: u32) -> Signal {
+        let (t, _) = self.phase(freq);
+        let modu = Duration::new(1, 0).div_f64(freq).as_nanos();
+        let div = 1u128 << octaves;
+        let cycle = (self.0.as_nanos() / modu) % div;
+        ((cycle as f64 + t) * 2.0 / div as f64 - 1.0).into()
+    }
+
+    /// Get the time between samples in seconds.
+    #[inline(always)]
+    pub(crate) fn period(&self) -> f64 {
+        self.1.as_secs_f64()
+    }
+
+    /// Get the phase (0 to 1) and phase increment per sample for a frequency.
+    #[inline(always)]
+    pub(crate) fn phase(&self, freq: f64) -> (f64, f64) {
+        let modu = Duration::new(1, 0).div_f64(freq).as_nanos();
+        let nano = self.0.as_nanos();
+        let step = self.1.as_nanos();
+        (
+            (nano % modu) as f64 / modu as f64,
+            step as f64 / modu as f64,
+        )
+    }
+}
+
+/// PolyBLEP (Polynomial Band-Limited Step) residual for phase `t` (0 to 1)
+/// with phase increment `dt`.
+#[inline(always)]
+fn poly_blep(t: f64, dt: f64) -> f64 {
+    if t < dt {
+        let t = t / dt;
+        2.0 * t - t * t - 1.0
+    } else if t > 1.0 - dt {
+        let t = (t - 1.0) / dt;
+        t * t + 2.0 * t + 1.0
+    } else {
+        0.0
+    }
 }
 
 /// A streaming synthesizer.  Implements [`Stream`](fon::Stream).
@@ -61,12 +170,30 @@ impl<T: Debug> Synth<T> {
     }
 }
 
+impl<T: Debug + Voice> Synth<T> {
+    /// Start playing a note.  Can be called between streaming audio from the
+    /// synthesizer, which passes the note to the parameters (for the
+    /// synthesizer function to play on the following samples).
+    #[inline(always)]
+    pub fn note_on(&mut self, note: Note, velocity: Velocity) {
+        self.params.note_on(note, velocity);
+    }
+
+    /// Release a note.  Can be called between streaming audio from the
+    /// synthesizer.
+    #[inline(always)]
+    pub fn note_off(&mut self, note: Note) {
+        self.params.note_off(note);
+    }
+}
+
 impl<T: Debug> Iterator for &mut Synth<T> {
     type Item = Mono64;
 
     fn next(&mut self) -> Option<Self::Item> {
         let frame =
-            (self.synthfn)(&mut self.params, Fc(self.counter)).to_mono();
+            (self.synthfn)(&mut self.params, Fc(self.counter, self.stepper))
+                .to_mono();
         self.counter += self.stepper;
         Some(frame)
     }
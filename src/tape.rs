@@ -0,0 +1,84 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{line::Line, sig::Signal, smooth::Smooth};
+use std::f64::consts::TAU;
+
+/// Rate of the slow (wow) delay time modulation in Hz.
+const WOW_RATE: f64 = 0.5;
+/// Rate of the fast (flutter) delay time modulation in Hz.
+const FLUTTER_RATE: f64 = 6.0;
+
+/// Tape delay emulation.  A feedback delay with wow and flutter modulation of
+/// the delay time, and saturation and lowpass damping in the feedback path.
+#[derive(Clone, Debug)]
+pub struct TapeDelay {
+    line: Line,
+    damping: Smooth,
+    sample_rate: f64,
+    // Wow and flutter depths in samples
+    wow: f64,
+    flutter: f64,
+    // Wow and flutter phases (0 to 1)
+    phase: [f64; 2],
+}
+
+impl TapeDelay {
+    /// Create a new tape delay effect.
+    ///
+    /// - `max_time`: The maximum delay time in seconds
+    /// - `wow`: Depth of slow delay time modulation in milliseconds
+    /// - `flutter`: Depth of fast delay time modulation in milliseconds
+    /// - `damping`: Cutoff frequency of the feedback lowpass in Hz
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        max_time: f64,
+        wow: f64,
+        flutter: f64,
+        damping: f64,
+    ) -> Self {
+        let sample_rate = sample_rate.into();
+        let max = (max_time * sample_rate).ceil()
+            + (wow + flutter) * 0.001 * sample_rate;
+        Self {
+            line: Line::new(max.ceil() as usize + 1),
+            damping: Smooth::new(sample_rate, (TAU * damping).recip()),
+            sample_rate,
+            wow: wow * 0.001 * sample_rate,
+            flutter: flutter * 0.001 * sample_rate,
+            phase: [0.0; 2],
+        }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `delay`: The delay time in samples (may be fractional)
+    /// - `feedback`: Gain of each repeat - range: 0~1
+    /// - `mix`: Wet/dry mix - range: 0~1 (0.0 for dry only, 1.0 for wet only)
+    #[inline(always)]
+    pub fn process(
+        &mut self,
+        input: Signal,
+        delay: f64,
+        feedback: f64,
+        mix: f64,
+    ) -> Signal {
+        let x = f64::from(input);
+        self.phase[0] = (self.phase[0] + WOW_RATE / self.sample_rate).fract();
+        self.phase[1] =
+            (self.phase[1] + FLUTTER_RATE / self.sample_rate).fract();
+        let modulation = self.wow * (1.0 + (TAU * self.phase[0]).sin()) * 0.5
+            + self.flutter * (1.0 + (TAU * self.phase[1]).sin()) * 0.5;
+        let wet = self.line.read(delay + modulation);
+        let damped = f64::from(self.damping.filter(wet.into()));
+        self.line.push(x + (damped * feedback).tanh());
+        (x * (1.0 - mix) + wet * mix).into()
+    }
+}
@@ -0,0 +1,331 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+/// Musical note division, for locking LFO rates and delay times to a tempo.
+/// A beat is a quarter note.
+///
+/// ```rust
+/// use twang::Division;
+///
+/// // Dotted eighth note delay at 120 BPM
+/// let time = Division::new(1, 8).dotted().seconds(120.0);
+/// assert_eq!(time, 0.375);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Division(f64);
+
+impl Division {
+    /// Create a new note division (for example, `Division::new(1, 4)` for a
+    /// quarter note, or `Division::new(2, 1)` for two whole notes).
+    #[inline(always)]
+    pub fn new(numerator: u32, denominator: u32) -> Self {
+        Self(4.0 * f64::from(numerator) / f64::from(denominator))
+    }
+
+    /// Lengthen by half (dotted note).
+    #[inline(always)]
+    pub fn dotted(self) -> Self {
+        Self(self.0 * 1.5)
+    }
+
+    /// Shorten to fit three in the space of two (triplet).
+    #[inline(always)]
+    pub fn triplet(self) -> Self {
+        Self(self.0 * 2.0 / 3.0)
+    }
+
+    /// Get the length in beats.
+    #[inline(always)]
+    pub fn beats(self) -> f64 {
+        self.0
+    }
+
+    /// Get the length in seconds at `bpm` beats per minute.
+    #[inline(always)]
+    pub fn seconds(self, bpm: f64) -> f64 {
+        self.0 * 60.0 / bpm
+    }
+
+    /// Get the length in samples at `bpm` beats per minute (for delay times).
+    #[inline(always)]
+    pub fn samples(self, bpm: f64, sample_rate: f64) -> f64 {
+        self.seconds(bpm) * sample_rate
+    }
+
+    /// Get the rate in Hz of one cycle per division, at `bpm` beats per
+    /// minute (for LFO rates).
+    #[inline(always)]
+    pub fn hz(self, bpm: f64) -> f64 {
+        self.seconds(bpm).recip()
+    }
+}
+
+/// Tempo clock and transport.  Keeps track of the tempo, time signature and
+/// play position, so that sequencers, LFOs and delays can all follow the same
+/// clock.  Call [`advance()`](Transport::advance) once per sample.
+///
+/// ```rust
+/// use twang::{Division, Transport};
+///
+/// // 120 BPM in 3/4 time
+/// let mut transport = Transport::new(48_000, 120.0);
+/// transport.time_signature(3, 4);
+/// transport.start();
+/// // Play for two seconds
+/// for _ in 0..96_000 {
+///     transport.advance();
+/// }
+/// assert!((transport.beats() - 4.0).abs() < 1e-9);
+/// assert_eq!(transport.bar(), 1);
+/// assert!((transport.beat() - 1.0).abs() < 1e-9);
+/// // Dotted eighth note delay time in samples
+/// let delay = transport.length(Division::new(1, 8).dotted());
+/// assert_eq!(delay, 18_000.0);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Transport {
+    sample_rate: f64,
+    bpm: f64,
+    numerator: u32,
+    denominator: u32,
+    // Play position in beats (quarter notes), and in samples
+    beats: f64,
+    samples: u64,
+    playing: bool,
+}
+
+impl Transport {
+    /// Create a new stopped transport in 4/4 time, at the start.
+    ///
+    /// - `bpm`: Tempo in beats (quarter notes) per minute
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R, bpm: f64) -> Self {
+        Self {
+            sample_rate: sample_rate.into(),
+            bpm,
+            numerator: 4,
+            denominator: 4,
+            beats: 0.0,
+            samples: 0,
+            playing: false,
+        }
+    }
+
+    /// Change the tempo in beats (quarter notes) per minute.  The play
+    /// position carries on from where it is.
+    #[inline(always)]
+    pub fn tempo(&mut self, bpm: f64) {
+        self.bpm = bpm;
+    }
+
+    /// Change the time signature (for example, `time_signature(6, 8)` for
+    /// 6/8 time).
+    #[inline(always)]
+    pub fn time_signature(&mut self, numerator: u32, denominator: u32) {
+        self.numerator = numerator.max(1);
+        self.denominator = denominator.max(1);
+    }
+
+    /// Start playing from the play position.
+    #[inline(always)]
+    pub fn start(&mut self) {
+        self.playing = true;
+    }
+
+    /// Stop playing (the play position stays where it is).
+    #[inline(always)]
+    pub fn stop(&mut self) {
+        self.playing = false;
+    }
+
+    /// Move the play position to a number of beats (quarter notes) from the
+    /// start.
+    #[inline(always)]
+    pub fn seek(&mut self, beats: f64) {
+        self.beats = beats.max(0.0);
+        self.samples = (self.beats * 60.0 / self.bpm * self.sample_rate) as u64;
+    }
+
+    /// Returns true while playing.
+    #[inline(always)]
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Get the tempo in beats (quarter notes) per minute.
+    #[inline(always)]
+    pub fn bpm(&self) -> f64 {
+        self.bpm
+    }
+
+    /// Get the play position in beats (quarter notes) from the start.
+    #[inline(always)]
+    pub fn beats(&self) -> f64 {
+        self.beats
+    }
+
+    /// Get the play position in samples from the start.
+    #[inline(always)]
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Get the bar of the play position (starting from 0).
+    #[inline(always)]
+    pub fn bar(&self) -> u64 {
+        (self.signature_beats() / f64::from(self.numerator)) as u64
+    }
+
+    /// Get the beat within the bar of the play position, in beats of the time
+    /// signature (starting from 0, fractional between beats).
+    #[inline(always)]
+    pub fn beat(&self) -> f64 {
+        self.signature_beats() % f64::from(self.numerator)
+    }
+
+    /// Get the number of whole divisions since the start (for stepping
+    /// sequencers).
+    #[inline(always)]
+    pub fn step(&self, division: Division) -> u64 {
+        (self.beats / division.beats()) as u64
+    }
+
+    /// Get the progress through the current division - range: 0~1 (for
+    /// phase-locked LFOs).
+    #[inline(always)]
+    pub fn phase(&self, division: Division) -> f64 {
+        (self.beats / division.beats()).fract()
+    }
+
+    /// Get the rate in Hz of one cycle per division at the current tempo.
+    #[inline(always)]
+    pub fn hz(&self, division: Division) -> f64 {
+        division.hz(self.bpm)
+    }
+
+    /// Get the length of a division in samples at the current tempo (for
+    /// delay times).
+    #[inline(always)]
+    pub fn length(&self, division: Division) -> f64 {
+        division.samples(self.bpm, self.sample_rate)
+    }
+
+    /// Advance the play position by one sample (if playing).
+    #[inline(always)]
+    pub fn advance(&mut self) {
+        if self.playing {
+            self.beats += self.bpm / (60.0 * self.sample_rate);
+            self.samples += 1;
+        }
+    }
+
+    /// Play position in beats of the time signature.
+    #[inline(always)]
+    fn signature_beats(&self) -> f64 {
+        self.beats * f64::from(self.denominator) / 4.0
+    }
+}
+
+/// Swing and groove timing, for a [`Sequencer`](crate::Sequencer).  Moves
+/// each step earlier or later by an offset (a fraction of a step), cycling
+/// through the offsets step by step, so patterns don't sound rigidly
+/// quantized.
+///
+/// ```rust
+/// use twang::Groove;
+///
+/// // Triplet swing (every second step is delayed by a third of a step)
+/// let swing = Groove::swing(2.0 / 3.0);
+/// // Custom groove over four steps, slightly rushing the third step
+/// let groove = Groove::new(&[0.0, 0.2, -0.05, 0.25]);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Groove(Vec<f64>);
+
+impl Groove {
+    /// Create a new custom groove.
+    ///
+    /// - `offsets`: How far to move each step, as a fraction of a step -
+    ///   range: -0.5~0.5 (negative for earlier, positive for later)
+    pub fn new(offsets: &[f64]) -> Self {
+        if offsets.is_empty() {
+            return Self::straight();
+        }
+        Self(
+            offsets
+                .iter()
+                .map(|offset| offset.clamp(-0.5, 0.5))
+                .collect(),
+        )
+    }
+
+    /// Create a new groove with no timing offsets.
+    pub fn straight() -> Self {
+        Self(vec![0.0])
+    }
+
+    /// Create a new swing groove, which delays every second step.
+    ///
+    /// - `ratio`: Fraction of each pair of steps taken by the first step -
+    ///   range: 0.5~0.75 (0.5 for straight, ⅔ for triplet swing)
+    pub fn swing(ratio: f64) -> Self {
+        Self(vec![0.0, 2.0 * ratio.clamp(0.5, 0.75) - 1.0])
+    }
+
+    /// Get the offset of each step.
+    #[inline(always)]
+    pub fn offsets(&self) -> &[f64] {
+        &self.0
+    }
+
+    /// Get the step at a straight position in steps from the start, and the
+    /// progress through that step (0 to 1).  Returns `None` before the first
+    /// step starts (when it's moved later).
+    pub(crate) fn step(&self, position: f64) -> Option<(u64, f64)> {
+        let len = self.0.len() as i64;
+        let start =
+            |step: i64| step as f64 + self.0[step.rem_euclid(len) as usize];
+        let mut step = position.floor() as i64;
+        if position < start(step) {
+            step -= 1;
+        } else if position >= start(step + 1) {
+            step += 1;
+        }
+        if step < 0 {
+            return None;
+        }
+        let length = (start(step + 1) - start(step)).max(f64::EPSILON);
+        Some((
+            step as u64,
+            ((position - start(step)) / length).clamp(0.0, 1.0),
+        ))
+    }
+}
+
+impl Default for Groove {
+    fn default() -> Self {
+        Self::straight()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groove_late_first_step() {
+        let groove = Groove::new(&[0.25, 0.0]);
+        assert_eq!(groove.step(0.0), None);
+        assert_eq!(groove.step(0.2), None);
+        assert_eq!(groove.step(0.25), Some((0, 0.0)));
+        assert_eq!(groove.step(1.0), Some((1, 0.0)));
+        assert_eq!(groove.step(2.0), Some((1, 0.8)));
+    }
+}
@@ -0,0 +1,90 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+use std::f64::consts::PI;
+
+/// Tilt equalizer.  Boosts high frequencies while cutting low frequencies (or
+/// the reverse) around a pivot frequency, with a single control.
+#[derive(Copy, Clone, Debug)]
+pub struct Tilt {
+    // Prewarped pivot frequency
+    pivot: f64,
+    state: f64,
+}
+
+impl Tilt {
+    /// Create a new tilt equalizer.
+    ///
+    /// - `pivot`: The frequency in Hz that is left unchanged (around 1 kHz is
+    ///   common)
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R, pivot: f64) -> Self {
+        let sample_rate = sample_rate.into();
+        Self {
+            pivot: (PI * pivot.clamp(0.0, sample_rate * 0.49) / sample_rate)
+                .tan(),
+            state: 0.0,
+        }
+    }
+
+    /// Filter the next sample of a signal.
+    ///
+    /// - `tilt`: Gain in dB applied to high frequencies (and the opposite to
+    ///   low frequencies) - positive is brighter, negative is darker
+    #[inline(always)]
+    pub fn filter(&mut self, input: Signal, tilt: f64) -> Signal {
+        let x = f64::from(input);
+        let gain = 10.0f64.powf(tilt / 20.0);
+        // Splitting at the pivot times the gain keeps the pivot at unity gain
+        let g = self.pivot * gain;
+        // One-pole lowpass (topology-preserving transform)
+        let v = (x - self.state) * g / (1.0 + g);
+        let lowpass = v + self.state;
+        self.state = lowpass + v;
+        let highpass = x - lowpass;
+        (lowpass / gain + highpass * gain).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Measure the gain in dB of a sine wave at `freq` through the filter.
+    fn gain(freq: f64, tilt: f64) -> f64 {
+        const S_RATE: f64 = 48_000.0;
+        let mut filter = Tilt::new(S_RATE, 1_000.0);
+        let (mut input, mut output) = (0.0, 0.0);
+        for i in 0..96_000 {
+            let x = (2.0 * PI * freq * i as f64 / S_RATE).sin();
+            let y = f64::from(filter.filter(x.into(), tilt));
+            // Skip the first second while the filter settles
+            if i >= 48_000 {
+                input += x * x;
+                output += y * y;
+            }
+        }
+        10.0 * (output / input).log10()
+    }
+
+    #[test]
+    fn pivot_unity() {
+        for tilt in [-12.0, -6.0, 6.0, 12.0].iter() {
+            assert!(gain(1_000.0, *tilt).abs() < 0.01, "tilt {}", tilt);
+        }
+    }
+
+    #[test]
+    fn extremes() {
+        assert!((gain(20.0, 6.0) + 6.0).abs() < 0.1);
+        assert!((gain(20_000.0, 6.0) - 6.0).abs() < 0.1);
+    }
+}
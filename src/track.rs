@@ -0,0 +1,37 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+/// Filter cutoff keyboard tracking.  Scales a filter cutoff with the note
+/// frequency, so high notes are brighter and low notes are more mellow.
+#[derive(Copy, Clone, Debug)]
+pub struct KeyTrack {
+    amount: f64,
+    reference: f64,
+}
+
+impl KeyTrack {
+    /// Create new keyboard tracking.
+    ///
+    /// - `amount`: Tracking amount - range: 0~1 (1.0 for the cutoff to follow
+    ///   the note exactly, 0.0 for no tracking)
+    /// - `reference`: Note frequency at which the cutoff is unchanged (such as
+    ///   middle C, 261.63 Hz)
+    #[inline(always)]
+    pub fn new(amount: f64, reference: f64) -> Self {
+        Self { amount, reference }
+    }
+
+    /// Get the tracked cutoff frequency for a `cutoff` at the reference note,
+    /// when playing a note at frequency `freq`.
+    #[inline(always)]
+    pub fn cutoff(&self, cutoff: f64, freq: f64) -> f64 {
+        cutoff * (freq / self.reference).powf(self.amount)
+    }
+}
@@ -0,0 +1,79 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+
+/// Reflection at the glottis (closed end).
+const GLOTTIS: f64 = 0.75;
+/// Reflection at the lips (open end).
+const LIPS: f64 = -0.85;
+/// Loss per section, per sample.
+const LOSS: f64 = 0.999;
+
+/// Vocal tract model.  A tube made of concatenated sections (Kelly-Lochbaum
+/// scattering junctions), meant to be excited by a glottal pulse source (see
+/// [`Signal::glottal()`](Signal::glottal)).
+///
+/// Each section is one sample long, so the number of sections sets the length
+/// of the tract (around 24 sections at 48 kHz for an adult-sized tract).
+#[derive(Clone, Debug)]
+pub struct Tract {
+    // Waves travelling right (towards the lips) and left (towards the glottis)
+    right: Vec<f64>,
+    left: Vec<f64>,
+    // Next values of the waves
+    next_right: Vec<f64>,
+    next_left: Vec<f64>,
+    // Reflection coefficients at the junction before each section
+    reflection: Vec<f64>,
+}
+
+impl Tract {
+    /// Create a new vocal tract with `sections` tube sections, all with the
+    /// same area.
+    pub fn new(sections: usize) -> Self {
+        let sections = sections.max(1);
+        Self {
+            right: vec![0.0; sections],
+            left: vec![0.0; sections],
+            next_right: vec![0.0; sections],
+            next_left: vec![0.0; sections],
+            reflection: vec![0.0; sections],
+        }
+    }
+
+    /// Change the cross-sectional area of each section (starting from the
+    /// glottis), which shapes the sound of the tract.
+    pub fn areas(&mut self, areas: &[f64]) {
+        assert_eq!(areas.len(), self.right.len(), "Tract: wrong area count");
+        for i in 1..areas.len() {
+            let (a, b) = (areas[i - 1].max(0.0), areas[i].max(0.0));
+            self.reflection[i] =
+                if a + b > 0.0 { (a - b) / (a + b) } else { 0.0 };
+        }
+    }
+
+    /// Filter the next sample of the glottal source through the tract.
+    #[inline(always)]
+    pub fn filter(&mut self, input: Signal) -> Signal {
+        let len = self.right.len();
+        let output = self.right[len - 1];
+        self.next_right[0] = self.left[0] * GLOTTIS + f64::from(input);
+        self.next_left[len - 1] = self.right[len - 1] * LIPS;
+        for i in 1..len {
+            let w = self.reflection[i] * (self.right[i - 1] + self.left[i]);
+            self.next_right[i] = (self.right[i - 1] - w) * LOSS;
+            self.next_left[i - 1] = (self.left[i] + w) * LOSS;
+        }
+        std::mem::swap(&mut self.right, &mut self.next_right);
+        std::mem::swap(&mut self.left, &mut self.next_left);
+        output.into()
+    }
+}
@@ -0,0 +1,53 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+
+/// Tremolo effect.  Modulates the amplitude of a signal with an internal LFO.
+#[derive(Copy, Clone, Debug)]
+pub struct Tremolo {
+    sample_rate: f64,
+    // LFO phase (0 to 1)
+    phase: f64,
+}
+
+impl Tremolo {
+    /// Create a new tremolo effect.
+    #[inline(always)]
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        Self {
+            sample_rate: sample_rate.into(),
+            phase: 0.0,
+        }
+    }
+
+    /// Process the next sample of a signal, using `wave` to shape the LFO's
+    /// sawtooth (for example, `Signal::sine`).
+    ///
+    /// - `rate`: LFO frequency in Hz
+    /// - `depth`: Amount of amplitude modulation - range: 0~1 (1.0 to fully
+    ///   silence the troughs)
+    #[inline(always)]
+    pub fn process<W>(
+        &mut self,
+        input: Signal,
+        rate: f64,
+        depth: f64,
+        wave: W,
+    ) -> Signal
+    where
+        W: Fn(Signal) -> Signal,
+    {
+        self.phase = (self.phase + rate / self.sample_rate).fract();
+        let lfo = f64::from(wave(Signal::from(self.phase * 2.0 - 1.0)));
+        let depth = depth.clamp(0.0, 1.0);
+        input.gain(1.0 - depth * (1.0 - lfo) * 0.5)
+    }
+}
@@ -0,0 +1,70 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{sig::Signal, synth::Fc, white::White};
+use std::f64::consts::FRAC_PI_4;
+
+/// Unison oscillator.  Stacks detuned copies of a waveform (for example, a
+/// sawtooth for the classic supersaw sound), panned across the stereo field.
+#[derive(Clone, Debug)]
+pub struct Unison {
+    // (frequency ratio, phase offset, left gain, right gain) per voice
+    voices: Vec<(f64, f64, f64, f64)>,
+}
+
+impl Unison {
+    /// Create a new unison oscillator.
+    ///
+    /// - `voices`: Number of detuned copies of the waveform
+    /// - `detune`: Total detune spread in cents
+    /// - `spread`: Stereo spread - range: 0~1 (0.0 for mono)
+    pub fn new(voices: usize, detune: f64, spread: f64) -> Self {
+        let gain = (voices.max(1) as f64).sqrt().recip();
+        let voices = (0..voices)
+            .map(|i| {
+                let pos = if voices > 1 {
+                    i as f64 / (voices - 1) as f64 * 2.0 - 1.0
+                } else {
+                    0.0
+                };
+                let ratio = 2.0f64.powf(pos * detune * 0.5 / 1200.0);
+                let angle = (pos * spread + 1.0) * FRAC_PI_4;
+                (ratio, 0.0, angle.cos() * gain, angle.sin() * gain)
+            })
+            .collect();
+        Self { voices }
+    }
+
+    /// Randomize the starting phase of each voice.
+    pub fn randomize(&mut self, noise: &mut White) {
+        for voice in self.voices.iter_mut() {
+            voice.1 = noise.noise().into();
+        }
+    }
+
+    /// Generate the next stereo (left, right) sample, using `wave` to shape
+    /// the sawtooth of each voice (for example, `Signal::sine`).
+    #[inline(always)]
+    pub fn gen<W>(&self, fc: &Fc, freq: f64, wave: W) -> [Signal; 2]
+    where
+        W: Fn(Signal) -> Signal,
+    {
+        self.voices.iter().fold(
+            [0.0.into(), 0.0.into()],
+            |[l, r], (ratio, phase, lgain, rgain)| {
+                let s = f64::from(wave(fc.freq(freq * ratio).shift(*phase)));
+                [
+                    (f64::from(l) + s * lgain).into(),
+                    (f64::from(r) + s * rgain).into(),
+                ]
+            },
+        )
+    }
+}
@@ -0,0 +1,44 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+use std::f64::consts::FRAC_PI_4;
+
+/// Vector synthesis crossfader.  Blends four sources based on a position on an
+/// X-Y plane, with equal-power crossfading.
+#[derive(Copy, Clone, Debug)]
+pub struct Vector([f64; 4]);
+
+impl Vector {
+    /// Create a new vector crossfader at a position.
+    ///
+    /// - `x`: Horizontal position - range: -1~1
+    /// - `y`: Vertical position - range: -1~1
+    #[inline(always)]
+    pub fn new<X: Into<Signal>, Y: Into<Signal>>(x: X, y: Y) -> Self {
+        let x = (f64::from(x.into()).clamp(-1.0, 1.0) + 1.0) * FRAC_PI_4;
+        let y = (f64::from(y.into()).clamp(-1.0, 1.0) + 1.0) * FRAC_PI_4;
+        let (left, right) = (x.cos(), x.sin());
+        let (bottom, top) = (y.cos(), y.sin());
+        Self([left * bottom, right * bottom, left * top, right * top])
+    }
+
+    /// Blend four sources, placed at the corners (-1, -1), (1, -1), (-1, 1)
+    /// and (1, 1).
+    #[inline(always)]
+    pub fn mix(&self, sources: [Signal; 4]) -> Signal {
+        sources
+            .iter()
+            .zip(self.0.iter())
+            .map(|(s, g)| f64::from(*s) * g)
+            .sum::<f64>()
+            .into()
+    }
+}
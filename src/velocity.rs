@@ -0,0 +1,60 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::curve::curve;
+
+/// How hard a note is played - range: 0~1.
+///
+/// ```rust
+/// use twang::Velocity;
+///
+/// // Cutoff only half follows velocity
+/// let cutoff = Velocity::from_midi(64).scale(4_000.0, 0.5, 0.0);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Velocity(f64);
+
+impl Velocity {
+    /// Create a new velocity (clamped to 0~1).
+    #[inline(always)]
+    pub fn new(velocity: f64) -> Self {
+        Self(velocity.clamp(0.0, 1.0))
+    }
+
+    /// Create a new velocity from a MIDI velocity (0~127).
+    #[inline(always)]
+    pub fn from_midi(velocity: u8) -> Self {
+        Self::new(f64::from(velocity) / 127.0)
+    }
+
+    /// Get the velocity - range: 0~1
+    #[inline(always)]
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    /// Scale an amount (envelope depth, cutoff, gain, …) by the velocity.
+    ///
+    /// - `sensitivity`: How much velocity affects the amount - range: 0~1
+    ///   (0.0 to always use the full amount, 1.0 for silence at velocity 0)
+    /// - `curvature`: Shape of the velocity response (0.0 for linear, positive
+    ///   to need harder playing, negative to respond more to soft playing)
+    #[inline(always)]
+    pub fn scale(self, amount: f64, sensitivity: f64, curvature: f64) -> f64 {
+        let sensitivity = sensitivity.clamp(0.0, 1.0);
+        amount * (1.0 - sensitivity + sensitivity * curve(self.0, curvature))
+    }
+}
+
+impl Default for Velocity {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
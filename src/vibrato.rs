@@ -0,0 +1,55 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{line::Line, sig::Signal};
+use std::f64::consts::TAU;
+
+/// Maximum delay time sweep in seconds (limits depth at very slow rates).
+const MAX_SWEEP: f64 = 0.02;
+
+/// Vibrato effect.  Modulates the pitch of a signal by sweeping the delay time
+/// of a short delay line with a sine LFO.
+#[derive(Clone, Debug)]
+pub struct Vibrato {
+    line: Line,
+    sample_rate: f64,
+    // LFO phase (0 to 1)
+    phase: f64,
+}
+
+impl Vibrato {
+    /// Create a new vibrato effect.
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        let sample_rate = sample_rate.into();
+        let max = (MAX_SWEEP * sample_rate).ceil() as usize + 2;
+        Self {
+            line: Line::new(max),
+            sample_rate,
+            phase: 0.0,
+        }
+    }
+
+    /// Process the next sample of a signal.
+    ///
+    /// - `rate`: LFO frequency in Hz
+    /// - `depth`: Maximum pitch deviation in cents
+    #[inline(always)]
+    pub fn process(&mut self, input: Signal, rate: f64, depth: f64) -> Signal {
+        self.phase = (self.phase + rate / self.sample_rate).fract();
+        // Pitch ratio is 1 minus the rate of change of the delay, so the
+        // sweep amplitude is set so that the peak rate of change matches depth
+        let ratio = (depth.abs() / 1200.0).exp2();
+        let sweep = ((ratio - 1.0) * self.sample_rate / (TAU * rate.max(0.01)))
+            .min(MAX_SWEEP * self.sample_rate * 0.5);
+        let delay = 1.0 + sweep * (1.0 + (TAU * self.phase).sin());
+        self.line.push(input.into());
+        self.line.read(delay).into()
+    }
+}
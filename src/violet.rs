@@ -0,0 +1,40 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{sig::Signal, white::White};
+use std::f64::consts::FRAC_1_SQRT_2;
+
+/// Gain to keep the output level similar to white noise.
+const GAIN: f64 = FRAC_1_SQRT_2;
+
+/// Violet (Purple) Noise Generator using differentiated white noise.
+#[derive(Default, Clone, Debug)]
+#[allow(missing_copy_implementations)]
+pub struct Violet {
+    white: White,
+    last: f64,
+}
+
+impl Violet {
+    /// Create a new Violet Noise Sampler.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get next sample of violet noise.
+    #[inline(always)]
+    pub fn noise(&mut self) -> Signal {
+        let noise = f64::from(self.white.noise());
+        let out = (noise - self.last) * GAIN;
+        self.last = noise;
+        Signal::from(out)
+    }
+}
@@ -0,0 +1,80 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{follower::Follower, sig::Signal, svf::Svf};
+
+/// Envelope follower attack time in seconds.
+const ATTACK: f64 = 0.002;
+/// Envelope follower release time in seconds.
+const RELEASE: f64 = 0.02;
+
+/// One band of a [`Vocoder`].
+#[derive(Copy, Clone, Debug)]
+struct Band {
+    modulator: Svf,
+    carrier: Svf,
+    follower: Follower,
+    freq: f64,
+}
+
+/// Channel vocoder.  Splits a modulator (such as a voice) into bands, and
+/// applies the level of each band to the same band of a carrier (such as a
+/// sawtooth synth).
+#[derive(Clone, Debug)]
+pub struct Vocoder {
+    bands: Vec<Band>,
+    q: f64,
+}
+
+impl Vocoder {
+    /// Create a new channel vocoder.
+    ///
+    /// - `bands`: Number of bands (for example, 16)
+    /// - `range`: Center frequencies in Hz of the lowest and highest band, the
+    ///   rest are evenly spaced in octaves between
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        bands: usize,
+        range: (f64, f64),
+    ) -> Self {
+        assert!(bands >= 2, "Vocoder needs at least 2 bands");
+        let sample_rate = sample_rate.into();
+        let ratio = (range.1 / range.0).powf(1.0 / (bands - 1) as f64);
+        let bands = (0..bands)
+            .map(|i| Band {
+                modulator: Svf::new(sample_rate),
+                carrier: Svf::new(sample_rate),
+                follower: Follower::new(sample_rate, ATTACK, RELEASE),
+                freq: range.0 * ratio.powi(i as i32),
+            })
+            .collect();
+        Self {
+            bands,
+            // Bandwidth of each band reaches the center of it's neighbors
+            q: ratio.sqrt() / (ratio - 1.0).max(f64::EPSILON),
+        }
+    }
+
+    /// Process the next sample of a `carrier`, shaped by a `modulator`.
+    #[inline(always)]
+    pub fn process(&mut self, carrier: Signal, modulator: Signal) -> Signal {
+        // Normalize bandpasses to unity gain at the center frequency.
+        let gain = self.q.recip();
+        let mut out = 0.0;
+        for band in self.bands.iter_mut() {
+            let band_mod = band.modulator.filter(modulator, band.freq, self.q);
+            let band_mod = band_mod.bandpass.gain(gain);
+            let level = f64::from(band.follower.follow(band_mod));
+            let carrier = band.carrier.filter(carrier, band.freq, self.q);
+            out += f64::from(carrier.bandpass) * gain * level;
+        }
+        out.into()
+    }
+}
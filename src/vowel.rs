@@ -0,0 +1,101 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{sig::Signal, synth::Fc};
+use std::f64::consts::{PI, TAU};
+
+/// Formants (frequency, gain in dB, bandwidth) of the vowels A, E, I, O, U.
+const VOWELS: [[(f64, f64, f64); 3]; 5] = [
+    [
+        (800.0, 0.0, 80.0),
+        (1150.0, -6.0, 90.0),
+        (2900.0, -32.0, 120.0),
+    ],
+    [
+        (350.0, 0.0, 60.0),
+        (2000.0, -20.0, 100.0),
+        (2800.0, -15.0, 120.0),
+    ],
+    [
+        (270.0, 0.0, 60.0),
+        (2140.0, -12.0, 90.0),
+        (2950.0, -26.0, 100.0),
+    ],
+    [
+        (450.0, 0.0, 70.0),
+        (800.0, -11.0, 80.0),
+        (2830.0, -22.0, 100.0),
+    ],
+    [
+        (325.0, 0.0, 50.0),
+        (700.0, -16.0, 60.0),
+        (2700.0, -35.0, 170.0),
+    ],
+];
+
+/// Attack time (in seconds) of each formant grain.
+const ATTACK: f64 = 0.002;
+
+/// Get the formants (frequency, linear gain, bandwidth) at a position (0 to 1)
+/// between the vowels A, E, I, O, U.
+pub(crate) fn formants(position: f64) -> [(f64, f64, f64); 3] {
+    let pos = position.clamp(0.0, 1.0) * 4.0;
+    let i = (pos as usize).min(3);
+    let frac = pos - i as f64;
+    let mut out = [(0.0, 0.0, 0.0); 3];
+    for (o, (a, b)) in out.iter_mut().zip(VOWELS[i].iter().zip(&VOWELS[i + 1]))
+    {
+        let lerp = |a: f64, b: f64| a + (b - a) * frac;
+        *o = (
+            lerp(a.0, b.0),
+            10.0f64.powf(lerp(a.1, b.1) / 20.0),
+            lerp(a.2, b.2),
+        );
+    }
+    out
+}
+
+/// Vowel (formant) oscillator.  Generates "talking synth" vowel timbres by
+/// summing a decaying sine grain for each formant, restarted every cycle.
+#[derive(Copy, Clone, Debug)]
+pub struct Vowel([(f64, f64, f64); 3]);
+
+impl Vowel {
+    /// Create a new vowel oscillator.
+    ///
+    /// - `vowel`: Morph between vowels - range: 0~1 (0.0 for A, 0.25 for E,
+    ///   0.5 for I, 0.75 for O, and 1.0 for U)
+    #[inline(always)]
+    pub fn new<S: Into<Signal>>(vowel: S) -> Self {
+        Self(formants(vowel.into().into()))
+    }
+
+    /// Generate the vowel using a frequency counter with a frequency.
+    #[inline(always)]
+    pub fn gen(&self, fc: &Fc, freq: f64) -> Signal {
+        let (t, _) = fc.phase(freq);
+        // Time since the start of the cycle
+        let time = t / freq;
+        let attack = ATTACK.min(0.5 / freq);
+        let window = if time < attack {
+            0.5 - 0.5 * (PI * time / attack).cos()
+        } else {
+            1.0
+        };
+        let out: f64 = self
+            .0
+            .iter()
+            .map(|(freq, gain, bw)| {
+                gain * (-PI * bw * time).exp() * (TAU * freq * time).sin()
+            })
+            .sum();
+        (out * window).into()
+    }
+}
@@ -0,0 +1,63 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{
+    follower::Follower,
+    sig::Signal,
+    svf::{Svf, SvfOutput},
+};
+
+/// Auto-wah (envelope filter).  Sweeps the cutoff of a resonant filter up as
+/// the level of the input rises.
+#[derive(Copy, Clone, Debug)]
+pub struct AutoWah {
+    svf: Svf,
+    follower: Follower,
+}
+
+impl AutoWah {
+    /// Create a new auto-wah.
+    ///
+    /// - `attack`: Time in seconds for the sweep to follow a rising level
+    /// - `release`: Time in seconds for the sweep to follow a falling level
+    pub fn new<R: Into<f64>>(
+        sample_rate: R,
+        attack: f64,
+        release: f64,
+    ) -> Self {
+        let sample_rate = sample_rate.into();
+        Self {
+            svf: Svf::new(sample_rate),
+            follower: Follower::new(sample_rate, attack, release),
+        }
+    }
+
+    /// Filter the next sample of a signal, returning all filter outputs
+    /// (bandpass and lowpass are the classic choices).
+    ///
+    /// - `sensitivity`: Gain applied to the level before sweeping
+    /// - `range`: Cutoff frequencies in Hz at silence, and at full level
+    /// - `q`: The quality factor of the filter (higher is more vocal)
+    #[inline(always)]
+    pub fn filter(
+        &mut self,
+        input: Signal,
+        sensitivity: f64,
+        range: (f64, f64),
+        q: f64,
+    ) -> SvfOutput {
+        let level = f64::from(self.follower.follow(input));
+        let amount = (level * sensitivity).clamp(0.0, 1.0);
+        // Sweep exponentially, so the sweep sounds even
+        let (low, high) = (range.0.max(1.0), range.1.max(1.0));
+        let cutoff = low * (high / low).powf(amount);
+        self.svf.filter(input, cutoff, q)
+    }
+}
@@ -0,0 +1,152 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::sig::Signal;
+
+/// Digital waveguide.  A pair of delay lines carrying waves in opposite
+/// directions, used to model strings, tubes, and membranes.
+///
+/// Each sample, read the waves arriving at each end with
+/// [`outputs()`](Waveguide::outputs), pass them through a [`Termination`] or
+/// [`Junction`], and feed the results back in with
+/// [`step()`](Waveguide::step).
+#[derive(Clone, Debug)]
+pub struct Waveguide {
+    right: Vec<f64>,
+    left: Vec<f64>,
+    pos: usize,
+}
+
+impl Waveguide {
+    /// Create a new waveguide that is `len` samples long.
+    #[inline(always)]
+    pub fn new(len: usize) -> Self {
+        let len = len.max(1);
+        Self {
+            right: vec![0.0; len],
+            left: vec![0.0; len],
+            pos: 0,
+        }
+    }
+
+    /// Get the length of the waveguide in samples.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.right.len()
+    }
+
+    /// Waveguides are never empty, so this always returns false.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Add a signal into both directions at `position` (0 is the left end).
+    #[inline(always)]
+    pub fn inject(&mut self, position: usize, signal: Signal) {
+        let len = self.len();
+        let half = f64::from(signal) * 0.5;
+        self.right[(self.pos + len - position % len) % len] += half;
+        self.left[(self.pos + position) % len] += half;
+    }
+
+    /// Get the sum of the waves travelling each direction at `position` (0
+    /// is the left end).
+    #[inline(always)]
+    pub fn pickup(&self, position: usize) -> Signal {
+        let len = self.len();
+        (self.right[(self.pos + len - position % len) % len]
+            + self.left[(self.pos + position) % len])
+            .into()
+    }
+
+    /// Get the waves arriving at the (left, right) ends of the waveguide.
+    #[inline(always)]
+    pub fn outputs(&self) -> (Signal, Signal) {
+        let len = self.len();
+        (
+            self.left[self.pos % len].into(),
+            self.right[(self.pos + 1) % len].into(),
+        )
+    }
+
+    /// Advance the waves by one sample, with new waves `left` and `right`
+    /// entering at the left and right ends of the waveguide.
+    #[inline(always)]
+    pub fn step(&mut self, left: Signal, right: Signal) {
+        let len = self.len();
+        self.pos = (self.pos + 1) % len;
+        self.right[self.pos] = left.into();
+        self.left[(self.pos + len - 1) % len] = right.into();
+    }
+}
+
+/// Waveguide termination.  Reflects waves arriving at the end of a waveguide
+/// with loss and damping.
+#[derive(Copy, Clone, Debug)]
+pub struct Termination {
+    reflection: f64,
+    damping: f64,
+    last: f64,
+}
+
+impl Termination {
+    /// Create a new waveguide termination.
+    ///
+    /// - `reflection`: Gain of the reflection (negative for a fixed end, such
+    ///   as a string, positive for an open end, such as a tube)
+    /// - `damping`: High frequency loss - range: 0~1
+    #[inline(always)]
+    pub fn new(reflection: f64, damping: f64) -> Self {
+        Self {
+            reflection,
+            damping: damping.clamp(0.0, 1.0),
+            last: 0.0,
+        }
+    }
+
+    /// Reflect an arriving wave.
+    #[inline(always)]
+    pub fn reflect(&mut self, signal: Signal) -> Signal {
+        let signal = f64::from(signal);
+        self.last = (1.0 - self.damping) * signal + self.damping * self.last;
+        (self.last * self.reflection).into()
+    }
+}
+
+/// Scattering junction between two waveguides with different impedances.
+#[derive(Copy, Clone, Debug)]
+pub struct Junction {
+    k: f64,
+}
+
+impl Junction {
+    /// Create a new scattering junction between waveguides with impedances
+    /// `a` and `b`.
+    #[inline(always)]
+    pub fn new(a: f64, b: f64) -> Self {
+        Self {
+            k: (a - b) / (a + b),
+        }
+    }
+
+    /// Scatter the wave arriving from waveguide a (`from_a`) and the wave
+    /// arriving from waveguide b (`from_b`), returning the waves leaving into
+    /// (waveguide a, waveguide b).
+    #[inline(always)]
+    pub fn scatter(&self, from_a: Signal, from_b: Signal) -> (Signal, Signal) {
+        let (a, b) = (f64::from(from_a), f64::from(from_b));
+        let k = self.k;
+        (
+            (k * a + (1.0 - k) * b).into(),
+            ((1.0 + k) * a - k * b).into(),
+        )
+    }
+}
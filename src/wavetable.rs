@@ -0,0 +1,184 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{sig::Signal, synth::Fc};
+use std::f64::consts::PI;
+
+/// Interpolation used when reading between samples of a table.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Interp {
+    /// Linear interpolation between the two nearest samples.
+    Linear,
+    /// Cubic (Catmull-Rom) interpolation between the four nearest samples.
+    Cubic,
+}
+
+/// Wavetable oscillator.  Stores one or more single-cycle tables that can be
+/// played back with a frequency counter, or morphed between.
+#[derive(Clone, Debug)]
+pub struct Wavetable {
+    // Each table is stored as a list of octave mipmaps, starting with the
+    // original full bandwidth table.
+    tables: Vec<Vec<Vec<f64>>>,
+    interp: Interp,
+}
+
+impl Wavetable {
+    /// Create a new empty wavetable oscillator.
+    #[inline(always)]
+    pub fn new(interp: Interp) -> Self {
+        Self {
+            tables: Vec::new(),
+            interp,
+        }
+    }
+
+    /// Add a single-cycle table to the end of the wavetable.
+    pub fn push(&mut self, table: &[f64]) {
+        assert!(!table.is_empty(), "Wavetable: table must not be empty");
+        self.tables.push(vec![table.to_vec()]);
+    }
+
+    /// Get the number of tables in the wavetable.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    /// Returns true if there are no tables in the wavetable.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// Generate band-limited tables for each octave, which are selected
+    /// automatically based on frequency during playback to reduce aliasing.
+    pub fn mipmap(&mut self) {
+        for table in self.tables.iter_mut() {
+            let orig = &table[0];
+            let len = orig.len();
+            // Discrete Fourier transform of the original table.
+            let harmonics: Vec<(f64, f64)> = (0..=len / 2)
+                .map(|k| {
+                    orig.iter().enumerate().fold(
+                        (0.0, 0.0),
+                        |(re, im), (i, v)| {
+                            let w = 2.0 * PI * (k * i) as f64 / len as f64;
+                            (re + v * w.cos(), im - v * w.sin())
+                        },
+                    )
+                })
+                .collect();
+            let mut mips = vec![orig.clone()];
+            let mut limit = len / 4;
+            while limit >= 1 {
+                let mip = (0..len)
+                    .map(|i| {
+                        let dc = harmonics[0].0 / len as f64;
+                        harmonics[1..=limit].iter().enumerate().fold(
+                            dc,
+                            |acc, (k, (re, im))| {
+                                let w = 2.0 * PI * ((k + 1) * i) as f64
+                                    / len as f64;
+                                acc + 2.0 * (re * w.cos() - im * w.sin())
+                                    / len as f64
+                            },
+                        )
+                    })
+                    .collect();
+                mips.push(mip);
+                limit /= 2;
+            }
+            table.truncate(1);
+            table.extend(mips.into_iter().skip(1));
+        }
+    }
+
+    /// Sample table number `table` with a phase signal (sawtooth, such as
+    /// from [`Fc::freq()`](Fc::freq)), using the full bandwidth table.
+    ///
+    /// # Panics
+    /// If there is no table number `table` (including when there are no
+    /// tables).
+    #[inline(always)]
+    pub fn sample(&self, table: usize, phase: Signal) -> Signal {
+        let t = (f64::from(phase) + 1.0) * 0.5;
+        self.read(&self.tables[table][0], t).into()
+    }
+
+    /// Play table number `table` using a frequency counter with a frequency,
+    /// selecting the mipmapped table appropriate for the frequency.
+    ///
+    /// # Panics
+    /// If there is no table number `table` (including when there are no
+    /// tables).
+    #[inline(always)]
+    pub fn gen(&self, table: usize, fc: &Fc, freq: f64) -> Signal {
+        let (t, dt) = fc.phase(freq);
+        self.read(self.mip(table, dt), t).into()
+    }
+
+    /// Play the wavetable using a frequency counter with a frequency,
+    /// smoothly crossfading between neighboring tables.  Silent if there are
+    /// no tables.
+    ///
+    /// - `position`: Position in the wavetable - range: 0~1 (0.0 for the first
+    ///   table, 1.0 for the last table)
+    #[inline(always)]
+    pub fn morph<S: Into<Signal>>(
+        &self,
+        fc: &Fc,
+        freq: f64,
+        position: S,
+    ) -> Signal {
+        if self.tables.is_empty() {
+            return Signal::from(0.0);
+        }
+        let (t, dt) = fc.phase(freq);
+        let last = self.tables.len() - 1;
+        let pos = f64::from(position.into()).clamp(0.0, 1.0) * last as f64;
+        let table = (pos as usize).min(last);
+        let frac = pos - table as f64;
+        let a = self.read(self.mip(table, dt), t);
+        if frac == 0.0 {
+            return a.into();
+        }
+        let b = self.read(self.mip(table + 1, dt), t);
+        (a + (b - a) * frac).into()
+    }
+
+    /// Select the mipmap for phase increment `dt`.
+    #[inline(always)]
+    pub(crate) fn mip(&self, table: usize, dt: f64) -> &[f64] {
+        let mips = &self.tables[table];
+        let level = (mips[0].len() as f64 * dt).log2().ceil().max(0.0);
+        &mips[(level as usize).min(mips.len() - 1)]
+    }
+
+    /// Read from a table at phase `t` (0 to 1).
+    #[inline(always)]
+    pub(crate) fn read(&self, table: &[f64], t: f64) -> f64 {
+        let len = table.len();
+        let pos = t.rem_euclid(1.0) * len as f64;
+        let i = pos as usize % len;
+        let frac = pos.fract();
+        let at = |o: usize| table[(i + o) % len];
+        match self.interp {
+            Interp::Linear => at(0) + (at(1) - at(0)) * frac,
+            Interp::Cubic => {
+                let (y0, y1, y2, y3) = (at(len - 1), at(0), at(1), at(2));
+                let a = -0.5 * y0 + 1.5 * y1 - 1.5 * y2 + 0.5 * y3;
+                let b = y0 - 2.5 * y1 + 2.0 * y2 - 0.5 * y3;
+                let c = -0.5 * y0 + 0.5 * y2;
+                ((a * frac + b) * frac + c) * frac + y1
+            }
+        }
+    }
+}
@@ -10,10 +10,12 @@
 
 use crate::sig::Signal;
 use core::num::Wrapping;
+use std::f64::consts::TAU;
 
 const SEQUENCE: u64 = 0xb5ad4eceda1ce2a9;
 
-/// White Noise Generator using Middle Square Weyl Sequence PRNG.
+/// White Noise Generator using Middle Square Weyl Sequence PRNG.  Can generate
+/// either uniform or gaussian white noise.
 #[derive(Default, Clone, Debug)]
 #[allow(missing_copy_implementations)]
 pub struct White {
@@ -28,6 +30,17 @@ impl White {
         Self::default()
     }
 
+    /// Create a new White Noise Sampler with a seed.  Samplers with different
+    /// seeds generate different noise (all samplers made with
+    /// [`new()`](White::new) generate the same noise).
+    #[inline(always)]
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            x: Wrapping(seed),
+            w: Wrapping(seed),
+        }
+    }
+
     /// Get next sample of white noise.
     #[inline(always)]
     pub fn noise(&mut self) -> Signal {
@@ -40,4 +53,16 @@ impl White {
             ((self.x.0 as i32) as f64 + 0.5) * (i32::MAX as f64 + 0.5).recip(),
         )
     }
+
+    /// Get next sample of gaussian white noise, with a standard deviation of
+    /// ⅓ (so almost all samples fall between -1 and 1).
+    #[inline(always)]
+    pub fn gaussian(&mut self) -> Signal {
+        // Box-Muller transform
+        // Range: (0, 1], since the log of 0 is -infinity
+        let a = (f64::from(self.noise()) + 1.0 + f64::EPSILON)
+            / (2.0 + f64::EPSILON);
+        let b = (f64::from(self.noise()) + 1.0) * 0.5;
+        Signal::from((-2.0 * a.ln()).sqrt() * (TAU * b).cos() / 3.0)
+    }
 }
@@ -0,0 +1,73 @@
+// Twang
+// Copyright © 2018-2021 Jeron Aldaron Lau.
+//
+// Licensed under any of:
+// - Apache License, Version 2.0 (https://www.apache.org/licenses/LICENSE-2.0)
+// - MIT License (https://mit-license.org/)
+// - Boost Software License, Version 1.0 (https://www.boost.org/LICENSE_1_0.txt)
+// At your choosing (See accompanying files LICENSE_APACHE_2_0.txt,
+// LICENSE_MIT.txt and LICENSE_BOOST_1_0.txt).
+
+use crate::{line::Line, sig::Signal, svf::Svf};
+use std::f64::consts::FRAC_1_SQRT_2;
+
+/// Maximum Haas delay in seconds.
+const MAX_HAAS: f64 = 0.03;
+/// Gain of the delayed mid signal added to the side signal.
+const HAAS_GAIN: f64 = 0.5;
+
+/// Stereo widener.  Scales the side (difference) of a stereo signal relative
+/// to the mid (sum), optionally adding a Haas micro-delay.  Only the side is
+/// ever processed, so the mono sum (left + right) is left unchanged.
+#[derive(Clone, Debug)]
+pub struct Widener {
+    line: Line,
+    highpass: Svf,
+    sample_rate: f64,
+}
+
+impl Widener {
+    /// Create a new stereo widener.
+    pub fn new<R: Into<f64>>(sample_rate: R) -> Self {
+        let sample_rate = sample_rate.into();
+        Self {
+            line: Line::new((MAX_HAAS * sample_rate).ceil() as usize + 1),
+            highpass: Svf::new(sample_rate),
+            sample_rate,
+        }
+    }
+
+    /// Process the next stereo (left, right) sample.
+    ///
+    /// - `width`: Side gain - range: 0+ (0.0 for mono, 1.0 for unchanged)
+    /// - `haas`: Delay in milliseconds of the mid signal added to the side
+    ///   signal, which pushes it toward the left - range: 0~30 (0.0 for off)
+    /// - `mono_below`: Frequency in Hz below which the side is removed, to
+    ///   keep bass centered (0.0 for off)
+    #[inline(always)]
+    pub fn process(
+        &mut self,
+        input: [Signal; 2],
+        width: f64,
+        haas: f64,
+        mono_below: f64,
+    ) -> [Signal; 2] {
+        let [l, r] = [f64::from(input[0]), f64::from(input[1])];
+        let mid = (l + r) * 0.5;
+        let mut side = (l - r) * 0.5;
+        self.line.push(mid);
+        if haas > 0.0 {
+            let delay = (haas * 0.001 * self.sample_rate).max(1.0);
+            side += self.line.read(delay) * HAAS_GAIN;
+        }
+        if mono_below > 0.0 {
+            side = self
+                .highpass
+                .filter(side.into(), mono_below, FRAC_1_SQRT_2)
+                .highpass
+                .into();
+        }
+        side *= width.max(0.0);
+        [(mid + side).into(), (mid - side).into()]
+    }
+}